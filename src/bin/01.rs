@@ -1,6 +1,8 @@
 use std::iter;
 
-advent_of_code::solution!(1);
+use advent_of_code::template::generate::Rng;
+
+advent_of_code::solution!(1, generator = generate);
 
 pub fn part_one(input: &str) -> Option<u32> {
     Some(
@@ -52,6 +54,24 @@ fn parse_number(input: &str) -> Option<u32> {
     }
 }
 
+/// Generates `scale` calibration lines of letters, digits and spelled out digits.
+fn generate(scale: usize, seed: u64) -> String {
+    const TOKENS: [&str; 15] = [
+        "a", "b", "x", "q", "n", "e", "1", "4", "7", "one", "two", "three", "six", "eight", "nine",
+    ];
+    let mut rng = Rng::new(seed);
+    (0..scale)
+        .map(|_| {
+            let mut line: Vec<&str> = (0..rng.range(2..12))
+                .map(|_| *rng.choose(&TOKENS))
+                .collect();
+            let digit = rng.range(0..line.len() as u64 + 1) as usize;
+            line.insert(digit, ["2", "3", "5", "8"][digit % 4]);
+            line.concat() + "\n"
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -87,4 +107,13 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file("inputs", DAY));
         assert_eq!(result, Some(53855));
     }
+
+    #[test]
+    fn test_generated() {
+        let input = generate(100, 1);
+        assert_eq!(input, generate(100, 1));
+        assert_eq!(input.lines().count(), 100);
+        assert!(part_one(&input).is_some());
+        assert!(part_two(&input).is_some());
+    }
 }
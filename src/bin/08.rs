@@ -87,7 +87,7 @@ impl<'a> Node<'a> {
 /// You can change the return type `OptimizedHashMap` to `HashMap` without any problems
 /// The solution takes then longer.
 /// This optimization was made to bring the time for part 2 under 1 ms
-fn parse(input: &str) -> (Vec<Dir>, OptimizedHashMap<Data, Node>) {
+fn parse(input: &str) -> (Vec<Dir>, OptimizedHashMap<Data<'_>, Node<'_>>) {
     let (left, right) = input.trim().split_once("\n\n").unwrap();
     let left = left.chars().map(|c| Dir::try_from(c).unwrap()).collect();
     let map = right
@@ -127,7 +127,7 @@ impl<'a> OptimizedHashMap<Data<'a>, Node<'a>> {
     fn get(&self, key: &Data<'a>) -> Option<&Node<'a>> {
         self.values.get(hash(key.data)).unwrap().as_ref()
     }
-    fn keys(&self) -> impl Iterator<Item = &Data> {
+    fn keys(&self) -> impl Iterator<Item = &Data<'_>> {
         self.keys.iter().filter_map(|e| e.as_ref())
     }
 }
@@ -1,8 +1,7 @@
-advent_of_code::solution!(9);
+advent_of_code::solution!(9, parse = parse);
 
-pub fn part_one(input: &str) -> Option<i32> {
-    let vec = parse(input);
-    Some(vec.into_iter().map(|v| recurse_part_one(&v)).sum())
+pub fn part_one(input: &[Vec<i32>]) -> Option<i32> {
+    Some(input.iter().map(|v| recurse_part_one(v)).sum())
 }
 fn reduction(x: &[i32]) -> Vec<i32> {
     x.windows(2).map(|s| s[1] - s[0]).collect()
@@ -19,9 +18,8 @@ fn recurse_part_two(x: &[i32]) -> i32 {
     }
     x.first().unwrap() - recurse_part_two(&reduction(x))
 }
-pub fn part_two(input: &str) -> Option<i32> {
-    let vec = parse(input);
-    Some(vec.into_iter().map(|v| recurse_part_two(&v)).sum())
+pub fn part_two(input: &[Vec<i32>]) -> Option<i32> {
+    Some(input.iter().map(|v| recurse_part_two(v)).sum())
 }
 fn parse(input: &str) -> Vec<Vec<i32>> {
     input
@@ -40,23 +38,27 @@ mod tests {
 
     #[test]
     fn test_part_one() {
-        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
+        let result = part_one(&parse(&advent_of_code::template::read_file(
+            "examples", DAY,
+        )));
         assert_eq!(result, Some(114));
     }
     #[test]
     fn test_part_one_actual() {
-        let result = part_one(&advent_of_code::template::read_file("inputs", DAY));
+        let result = part_one(&parse(&advent_of_code::template::read_file("inputs", DAY)));
         assert_eq!(result, Some(1_934_898_178));
     }
 
     #[test]
     fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
+        let result = part_two(&parse(&advent_of_code::template::read_file(
+            "examples", DAY,
+        )));
         assert_eq!(result, Some(2));
     }
     #[test]
     fn test_part_two_actual() {
-        let result = part_two(&advent_of_code::template::read_file("inputs", DAY));
+        let result = part_two(&parse(&advent_of_code::template::read_file("inputs", DAY)));
         assert_eq!(result, Some(1_129));
     }
 }
@@ -1,38 +1,10 @@
-use std::{
-    collections::{HashMap, VecDeque},
-    str::FromStr,
-};
+use std::str::FromStr;
 
-advent_of_code::solution!(12);
-#[derive(PartialEq, Clone, Copy, Eq, Hash)]
-enum Status {
-    Operational,
-    Damaged,
-    Unknown,
-}
-impl std::fmt::Debug for Status {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Self::Operational => write!(f, "."),
-            Self::Damaged => write!(f, "#"),
-            Self::Unknown => write!(f, "?"),
-        }
-    }
-}
-impl TryFrom<char> for Status {
-    type Error = char;
+use advent_of_code::nonogram;
 
-    fn try_from(value: char) -> Result<Self, Self::Error> {
-        match value {
-            '.' => Ok(Self::Operational),
-            '#' => Ok(Self::Damaged),
-            '?' => Ok(Self::Unknown),
-            c => Err(c),
-        }
-    }
-}
+advent_of_code::solution!(12);
 struct Group {
-    springs: Vec<Status>,
+    springs: Vec<u8>,
     contiguous: Vec<usize>,
 }
 impl FromStr for Group {
@@ -40,10 +12,7 @@ impl FromStr for Group {
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let (springs, contiguous) = s.split_once(' ').unwrap();
-        let springs = springs
-            .chars()
-            .map(|c| Status::try_from(c).unwrap())
-            .collect();
+        let springs = springs.as_bytes().to_vec();
         let contiguous = contiguous.split(',').map(|n| n.parse().unwrap()).collect();
         Ok(Self {
             springs,
@@ -53,97 +22,20 @@ impl FromStr for Group {
 }
 impl Group {
     fn quintuple(&mut self) {
-        let len = self.contiguous.len();
-        self.contiguous = self
-            .contiguous
-            .iter()
-            .cloned()
-            .cycle()
-            .take(5 * len)
-            .collect();
-        let vec = vec![self.springs.clone(); 5]
-            .into_iter()
-            .collect::<Vec<_>>();
-        self.springs = vec.as_slice().join(&Status::Unknown);
+        self.contiguous = self.contiguous.repeat(5);
+        self.springs = [self.springs.as_slice(); 5].join(&nonogram::UNKNOWN);
+    }
+    fn arrangements(&self) -> u64 {
+        nonogram::count(&self.springs, &self.contiguous)
     }
 }
-pub fn part_one(input: &str) -> Option<usize> {
-    let vec = parse(input);
-    let vec = vec
-        .into_iter()
-        .map(|g| ((g.springs), g.contiguous))
-        .collect::<VecDeque<_>>();
-    Some(
-        vec.into_iter()
-            .map(|(springs, contiguous)| solve(&springs, &contiguous, &mut HashMap::new()))
-            .sum(),
-    )
-}
-fn solve(
-    springs: &[Status],
-    contiguous: &[usize],
-    map: &mut HashMap<(usize, usize), usize>,
-) -> usize {
-    // Memoisation
-    if let Some(x) = map.get(&(springs.len(), contiguous.len())) {
-        return *x;
-    }
-    // Base Cases
-    if contiguous.is_empty() {
-        return if springs.contains(&Status::Damaged) {
-            // if Damaged is left but there should be none
-            0
-        } else {
-            // if Damaged is not there and there should be none
-            1
-        };
-    }
-    if springs.len() < contiguous.iter().sum::<usize>() + contiguous.len() - 1 {
-        // There are not enough springs left to match the contiguous
-        return 0;
-    }
-    let erg = match springs[0] {
-        Status::Operational => solve(&springs[1..], contiguous, map),
-        Status::Damaged => damaged(springs, contiguous, map),
-        Status::Unknown => {
-            damaged(springs, contiguous, map) + solve(&springs[1..], contiguous, map)
-        }
-    };
-    map.insert((springs.len(), contiguous.len()), erg);
-    erg
-}
-fn damaged(
-    springs: &[Status],
-    contiguous: &[usize],
-    map: &mut HashMap<(usize, usize), usize>,
-) -> usize {
-    // not enougth springs left or not the required length of springs
-    if springs.len() < contiguous[0] || springs[0..contiguous[0]].contains(&Status::Operational) {
-        return 0;
-    }
-    // If there are exactly as many springs left as required
-    if springs.len() == contiguous[0] {
-        return if contiguous.len() == 1 { 1 } else { 0 };
-    }
-    // Check the element after the block
-    if springs[contiguous[0]] == Status::Damaged {
-        return 0;
-    }
-    solve(&springs[(contiguous[0] + 1)..], &contiguous[1..], map)
+pub fn part_one(input: &str) -> Option<u64> {
+    Some(parse(input).iter().map(Group::arrangements).sum())
 }
-pub fn part_two(input: &str) -> Option<usize> {
+pub fn part_two(input: &str) -> Option<u64> {
     let mut vec = parse(input);
     vec.iter_mut().for_each(|g| g.quintuple());
-    let vec = vec;
-    let vec = vec
-        .into_iter()
-        .map(|g| (g.springs, g.contiguous))
-        .collect::<Vec<_>>();
-    Some(
-        vec.into_iter()
-            .map(|(springs, contiguous)| solve(&springs, &contiguous, &mut HashMap::new()))
-            .sum(),
-    )
+    Some(vec.iter().map(Group::arrangements).sum())
 }
 fn parse(input: &str) -> Vec<Group> {
     input
@@ -208,7 +208,7 @@ fn dijkstra(
         let (_, u) = queue.pop().unwrap();
         let next = map.get(&u).unwrap();
         for v in next {
-            let alt = dist.get(&u).map(|n| (*n + v.value)).unwrap_or(u32::MAX);
+            let alt = dist.get(&u).map(|n| *n + v.value).unwrap_or(u32::MAX);
             if alt < *dist.get(v).unwrap_or(&u32::MAX) {
                 dist.insert(*v, alt);
                 queue.push((Reverse(alt), *v));
@@ -1,5 +1,7 @@
 use std::str::FromStr;
 
+use advent_of_code::{geometry::Direction, Polygon};
+
 advent_of_code::solution!(18);
 enum Dir {
     Up,
@@ -62,37 +64,16 @@ pub fn part_two(input: &str) -> Option<u64> {
     solve(vec)
 }
 fn solve(vec: &[Instruction]) -> Option<u64> {
-    // https://en.wikipedia.org/wiki/Pick%27s_theorem
-    let shoelance = shoelance(vec);
-    let perimeter = vec.iter().map(|i| i.length).sum::<u64>();
-    assert_eq!(perimeter % 2, 0);
-
-    Some(shoelance + perimeter / 2 + 1)
-}
-fn shoelance(vec: &[Instruction]) -> u64 {
-    // https://en.wikipedia.org/wiki/Shoelace_formula
-    let mut curr = (0, 0);
-    let mut points = Vec::with_capacity(vec.len());
-    points.push(curr);
-    for ins in vec {
-        curr = match ins.dir {
-            Dir::Up => (curr.0 + ins.length as i64, curr.1),
-            Dir::Down => (curr.0 - ins.length as i64, curr.1),
-            Dir::Left => (curr.0, curr.1 - ins.length as i64),
-            Dir::Right => (curr.0, curr.1 + ins.length as i64),
+    let polygon = Polygon::from_moves(vec.iter().map(|ins| {
+        let dir = match ins.dir {
+            Dir::Up => Direction::Up,
+            Dir::Down => Direction::Down,
+            Dir::Left => Direction::Left,
+            Dir::Right => Direction::Right,
         };
-        points.push(curr);
-    }
-    let mut points = points.into_iter().rev().collect::<Vec<_>>();
-    let n = points.len();
-    points.push(points[0]);
-    let mut sum = 0;
-    for i in 0..n {
-        sum += (points[i].0 + points[i + 1].0) * (points[i].1 - points[i + 1].1);
-    }
-    assert!(sum >= 0);
-    assert_eq!(sum % 2, 0);
-    sum as u64 / 2
+        (dir, ins.length as i64)
+    }))?;
+    Some(polygon.lattice_points() as u64)
 }
 fn parse_part_1(input: &str) -> Vec<Instruction> {
     input.trim().lines().map(Instruction::part_1).collect()
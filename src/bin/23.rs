@@ -218,7 +218,7 @@ fn execute(input: &str) -> Option<u32> {
     Some(erg.unwrap())
 }
 pub fn part_two(input: &str) -> Option<u32> {
-    let input = input.replace(|ch| matches!(ch, '<' | '>' | '^' | 'v'), ".");
+    let input = input.replace(['<', '>', '^', 'v'], ".");
     execute(&input)
 }
 fn parse(input: &str) -> Grid<Tile> {
@@ -1,6 +1,6 @@
 use advent_of_code::{i256::i256, Grid, OwnIndex};
 
-use std::{iter::repeat, str::FromStr};
+use std::{iter::repeat_n, str::FromStr};
 /// This function solves a * x = b where a is a matrix and x is unknown
 fn solve_lgs(a: &Grid<i256>, b: Vec<i256>) -> Vec<i256> {
     debug_assert_eq!(a.width(), a.height(), "a has to be square");
@@ -13,7 +13,7 @@ fn solve_lgs(a: &Grid<i256>, b: Vec<i256>) -> Vec<i256> {
 }
 fn matrix_multiplication(a: &Grid<i256>, b: &Grid<i256>) -> Grid<i256> {
     debug_assert_eq!(a.width(), b.height());
-    let mut ret = Grid::from_iter(repeat(i256::ZERO).take(a.height() * b.width()), b.width());
+    let mut ret = Grid::from_iter(repeat_n(i256::ZERO, a.height() * b.width()), b.width());
     for y in 0..ret.height() {
         for x in 0..ret.width() {
             let mut erg = i256::ZERO;
@@ -55,7 +55,7 @@ fn minor(matrix: &Grid<i256>, index: (usize, usize)) -> i256 {
     ))
 }
 fn cofactor(matrix: &Grid<i256>, index: (usize, usize)) -> i256 {
-    let sign = if (index.0 + index.1) % 2 == 0 {
+    let sign = if (index.0 + index.1).is_multiple_of(2) {
         i256::ONE
     } else {
         -i256::ONE
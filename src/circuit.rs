@@ -0,0 +1,497 @@
+/// Circuits of components that send high and low pulses to each other, e.g. 2023 day 20.
+///
+/// A circuit is described by one line per component, `broadcaster -> a, b`,
+/// `%a -> b` for a flip-flop and `&b -> out` for a conjunction. Names that only appear
+/// as outputs become [`Kind::Output`] components.
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::error::Error;
+use std::fmt::{Display, Write};
+use std::str::FromStr;
+
+use crate::lcm_over_slice;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Kind {
+    /// Sends every pulse it receives to all outputs, it receives the pulse of the button.
+    Broadcaster,
+    /// Ignores high pulses, a low pulse toggles it and it sends high if it turned on, low otherwise.
+    FlipFlop,
+    /// Remembers the last pulse of every input and sends low if all of them were high, high otherwise.
+    Conjunction,
+    /// Receives pulses without reacting to them.
+    Output,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Component {
+    pub name: String,
+    pub kind: Kind,
+    pub inputs: Vec<usize>,
+    pub outputs: Vec<usize>,
+}
+
+/// A pulse on the wire from one component to another.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Pulse {
+    pub from: usize,
+    pub to: usize,
+    pub high: bool,
+}
+
+/// The repetition of the states of a part of a circuit over button presses, see [`Circuit::find_cycle`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    /// The number of presses after which the states start to repeat.
+    pub start: u64,
+    pub length: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct Circuit {
+    components: Vec<Component>,
+    names: HashMap<String, usize>,
+    broadcaster: usize,
+    /// Whether every flip-flop is on.
+    on: Vec<bool>,
+    /// The last pulse of every input of every conjunction, in the order of [`Component::inputs`].
+    memory: Vec<Vec<bool>>,
+    presses: u64,
+    low: u64,
+    high: u64,
+    /// The press in which every component sent a high pulse for the first time.
+    first_high: Vec<Option<u64>>,
+}
+
+impl Circuit {
+    pub fn components(&self) -> &[Component] {
+        &self.components
+    }
+
+    pub fn id(&self, name: &str) -> Option<usize> {
+        self.names.get(name).copied()
+    }
+
+    /// The number of times the button was pressed.
+    pub fn presses(&self) -> u64 {
+        self.presses
+    }
+
+    /// The number of low pulses that were sent, including those of the button.
+    pub fn low_pulses(&self) -> u64 {
+        self.low
+    }
+
+    pub fn high_pulses(&self) -> u64 {
+        self.high
+    }
+
+    /// Returns the circuit to the state before the first press: all flip-flops are off
+    /// and all conjunctions remember low pulses.
+    pub fn reset(&mut self) {
+        self.on.fill(false);
+        self.memory.iter_mut().for_each(|m| m.fill(false));
+        self.presses = 0;
+        self.low = 0;
+        self.high = 0;
+        self.first_high.fill(None);
+    }
+
+    /// Presses the button, which sends a low pulse to the broadcaster, and processes pulses
+    /// until the circuit is quiet. Pulses are processed in the order they are sent.
+    /// Returns every pulse of the press in that order.
+    pub fn press(&mut self) -> Vec<Pulse> {
+        self.presses += 1;
+        let mut pulses = vec![];
+        let mut queue = VecDeque::from([Pulse {
+            from: self.broadcaster,
+            to: self.broadcaster,
+            high: false,
+        }]);
+        while let Some(pulse) = queue.pop_front() {
+            pulses.push(pulse);
+            if pulse.high {
+                self.high += 1;
+            } else {
+                self.low += 1;
+            }
+
+            let Some(high) = self.receive(pulse) else {
+                continue;
+            };
+            let component = &self.components[pulse.to];
+            if high {
+                self.first_high[pulse.to].get_or_insert(self.presses);
+            }
+            queue.extend(component.outputs.iter().map(|&to| Pulse {
+                from: pulse.to,
+                to,
+                high,
+            }));
+        }
+        pulses
+    }
+
+    /// Updates the receiver of a pulse and returns the pulse it sends in turn, if any.
+    fn receive(&mut self, pulse: Pulse) -> Option<bool> {
+        let component = &self.components[pulse.to];
+        match component.kind {
+            Kind::Broadcaster => Some(pulse.high),
+            Kind::FlipFlop if pulse.high => None,
+            Kind::FlipFlop => {
+                self.on[pulse.to] = !self.on[pulse.to];
+                Some(self.on[pulse.to])
+            }
+            Kind::Conjunction => {
+                let input = component.inputs.iter().position(|&i| i == pulse.from)?;
+                let memory = &mut self.memory[pulse.to];
+                memory[input] = pulse.high;
+                Some(!memory.iter().all(|&high| high))
+            }
+            Kind::Output => None,
+        }
+    }
+
+    /// The press in which the component `name` sent a high pulse for the first time.
+    pub fn first_high(&self, name: &str) -> Option<u64> {
+        self.first_high[self.id(name)?]
+    }
+
+    /// Presses the button until the component `name` sends a high pulse, at most `limit` times.
+    /// Returns the number of that press counted from the start.
+    pub fn press_until_high(&mut self, name: &str, limit: u64) -> Option<u64> {
+        let id = self.id(name)?;
+        for _ in 0..limit {
+            if self.first_high[id].is_some() {
+                break;
+            }
+            self.press();
+        }
+        self.first_high[id]
+    }
+
+    /// Splits the circuit into the parts that only receive pulses from one output of the broadcaster.
+    /// Components that are reached from several outputs, e.g. where the parts are merged again,
+    /// belong to no part.
+    pub fn subcircuits(&self) -> Vec<Vec<usize>> {
+        let reachable: Vec<HashSet<usize>> = self.components[self.broadcaster]
+            .outputs
+            .iter()
+            .map(|&start| {
+                let mut seen = HashSet::from([start]);
+                let mut queue = VecDeque::from([start]);
+                while let Some(curr) = queue.pop_front() {
+                    for &next in &self.components[curr].outputs {
+                        if next != self.broadcaster && seen.insert(next) {
+                            queue.push_back(next);
+                        }
+                    }
+                }
+                seen
+            })
+            .collect();
+
+        reachable
+            .iter()
+            .enumerate()
+            .map(|(i, part)| {
+                let mut part: Vec<usize> = part
+                    .iter()
+                    .copied()
+                    .filter(|c| {
+                        reachable
+                            .iter()
+                            .enumerate()
+                            .all(|(j, o)| i == j || !o.contains(c))
+                    })
+                    .collect();
+                part.sort_unstable();
+                part
+            })
+            .collect()
+    }
+
+    /// The state of some components: the flip-flops and the memory of the conjunctions.
+    fn state(&self, members: &[usize]) -> Vec<bool> {
+        members
+            .iter()
+            .flat_map(|&c| std::iter::once(self.on[c]).chain(self.memory[c].iter().copied()))
+            .collect()
+    }
+
+    /// Finds after how many presses, starting from the current state, the state of `members`
+    /// repeats. Returns the cycle and the presses, relative to the current one, in which each
+    /// of the members sent a high pulse until the state repeated.
+    pub fn find_cycle(&self, members: &[usize]) -> (Cycle, HashMap<usize, Vec<u64>>) {
+        let mut circuit = self.clone();
+        let mut seen = HashMap::from([(circuit.state(members), 0)]);
+        let mut highs: HashMap<usize, Vec<u64>> = HashMap::new();
+        for press in 1.. {
+            for pulse in circuit.press() {
+                if pulse.high && members.contains(&pulse.from) {
+                    let presses = highs.entry(pulse.from).or_default();
+                    if presses.last() != Some(&press) {
+                        presses.push(press);
+                    }
+                }
+            }
+            if let Some(start) = seen.insert(circuit.state(members), press) {
+                let cycle = Cycle {
+                    start,
+                    length: press - start,
+                };
+                return (cycle, highs);
+            }
+        }
+        unreachable!()
+    }
+
+    /// The number of presses until the component `sink` receives a low pulse for the first time,
+    /// for circuits like the one of 2023 day 20: `sink` is fed by a single conjunction, every input
+    /// of which is the exit of a separate subcircuit that sends a high pulse in every `n`-th press
+    /// and in no other, where `n` is the length of the cycle of its states.
+    ///
+    /// Returns [`None`] for circuits that do not have this shape.
+    pub fn presses_until_low(&self, sink: &str) -> Option<u64> {
+        let sink = self.id(sink)?;
+        let [feeder] = self.components[sink].inputs[..] else {
+            return None;
+        };
+        if self.components[feeder].kind != Kind::Conjunction {
+            return None;
+        }
+
+        let subcircuits = self.subcircuits();
+        let mut lengths = vec![];
+        for &exit in &self.components[feeder].inputs {
+            let members = subcircuits.iter().find(|part| part.contains(&exit))?;
+            let (cycle, highs) = self.find_cycle(members);
+            let periodic: Vec<u64> = (1..=(cycle.start + cycle.length) / cycle.length)
+                .map(|k| k * cycle.length)
+                .collect();
+            if highs.get(&exit)? != &periodic {
+                return None;
+            }
+            lengths.push(cycle.length);
+        }
+        Some(lcm_over_slice(&lengths))
+    }
+
+    /// Describes the circuit in the DOT language of Graphviz,
+    /// e.g. to render it with `dot -Tsvg circuit.dot > circuit.svg`.
+    pub fn to_dot(&self) -> String {
+        let mut dot = "digraph circuit {\n".to_string();
+        for component in &self.components {
+            let (label, shape) = match component.kind {
+                Kind::Broadcaster => (component.name.clone(), "doubleoctagon"),
+                Kind::FlipFlop => (format!("%{}", component.name), "box"),
+                Kind::Conjunction => (format!("&{}", component.name), "invhouse"),
+                Kind::Output => (component.name.clone(), "ellipse"),
+            };
+            let _ = writeln!(
+                dot,
+                "  \"{}\" [label=\"{label}\", shape={shape}];",
+                component.name
+            );
+        }
+        for component in &self.components {
+            for &output in &component.outputs {
+                let _ = writeln!(
+                    dot,
+                    "  \"{}\" -> \"{}\";",
+                    component.name, self.components[output].name
+                );
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+impl FromStr for Circuit {
+    type Err = CircuitFromStrError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut components: Vec<Component> = vec![];
+        let mut names: HashMap<String, usize> = HashMap::new();
+        let mut id = |name: &str, components: &mut Vec<Component>| {
+            *names.entry(name.to_string()).or_insert_with(|| {
+                components.push(Component {
+                    name: name.to_string(),
+                    kind: Kind::Output,
+                    inputs: vec![],
+                    outputs: vec![],
+                });
+                components.len() - 1
+            })
+        };
+
+        for line in s.lines().map(str::trim).filter(|l| !l.is_empty()) {
+            let error = || CircuitFromStrError(line.to_string());
+            let (left, right) = line.split_once("->").ok_or_else(error)?;
+            let left = left.trim();
+            let (kind, name) = if let Some(name) = left.strip_prefix('%') {
+                (Kind::FlipFlop, name)
+            } else if let Some(name) = left.strip_prefix('&') {
+                (Kind::Conjunction, name)
+            } else if left == "broadcaster" {
+                (Kind::Broadcaster, left)
+            } else {
+                return Err(error());
+            };
+
+            let from = id(name, &mut components);
+            if components[from].kind != Kind::Output {
+                return Err(error());
+            }
+            components[from].kind = kind;
+            for output in right.split(',').map(str::trim).filter(|o| !o.is_empty()) {
+                let to = id(output, &mut components);
+                components[from].outputs.push(to);
+                components[to].inputs.push(from);
+            }
+        }
+
+        let broadcaster = components
+            .iter()
+            .position(|c| c.kind == Kind::Broadcaster)
+            .ok_or_else(|| CircuitFromStrError("broadcaster".to_string()))?;
+        let n = components.len();
+        Ok(Self {
+            memory: components
+                .iter()
+                .map(|c| vec![false; c.inputs.len()])
+                .collect(),
+            names: components
+                .iter()
+                .enumerate()
+                .map(|(i, c)| (c.name.clone(), i))
+                .collect(),
+            components,
+            broadcaster,
+            on: vec![false; n],
+            presses: 0,
+            low: 0,
+            high: 0,
+            first_high: vec![None; n],
+        })
+    }
+}
+
+/// A line of a circuit that could not be parsed, or a missing broadcaster.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CircuitFromStrError(String);
+
+impl Error for CircuitFromStrError {}
+
+impl Display for CircuitFromStrError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid component: {}", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SIMPLE: &str = "broadcaster -> a, b, c\n%a -> b\n%b -> c\n%c -> inv\n&inv -> a";
+    const INTERESTING: &str =
+        "broadcaster -> a\n%a -> inv, con\n&inv -> b\n%b -> con\n&con -> output";
+
+    /// Two independent binary counters that are merged by a conjunction into `rx`.
+    const COUNTERS: &str = "\
+broadcaster -> a0, b0
+%a0 -> a1, ea
+%a1 -> ea
+&ea -> ia, a0
+&ia -> out
+%b0 -> b1, eb
+%b1 -> b2, eb
+%b2 -> eb
+&eb -> ib, b0
+&ib -> out
+&out -> rx";
+
+    fn pulses(input: &str, presses: usize) -> (u64, u64) {
+        let mut circuit: Circuit = input.parse().unwrap();
+        (0..presses).for_each(|_| {
+            circuit.press();
+        });
+        (circuit.low_pulses(), circuit.high_pulses())
+    }
+
+    #[test]
+    fn test_pulses() {
+        assert_eq!(pulses(SIMPLE, 1), (8, 4));
+        assert_eq!(pulses(SIMPLE, 1000), (8000, 4000));
+        assert_eq!(pulses(INTERESTING, 1000), (4250, 2750));
+
+        let mut circuit: Circuit = SIMPLE.parse().unwrap();
+        let order: Vec<(String, bool)> = circuit
+            .press()
+            .iter()
+            .take(5)
+            .map(|p| (circuit.components()[p.to].name.clone(), p.high))
+            .collect();
+        assert_eq!(
+            order,
+            [
+                ("broadcaster".to_string(), false),
+                ("a".to_string(), false),
+                ("b".to_string(), false),
+                ("c".to_string(), false),
+                ("b".to_string(), true),
+            ]
+        );
+        circuit.reset();
+        assert_eq!((circuit.presses(), circuit.low_pulses()), (0, 0));
+    }
+
+    #[test]
+    fn test_watch() {
+        let mut circuit: Circuit = INTERESTING.parse().unwrap();
+        assert_eq!(circuit.press_until_high("con", 10), Some(1));
+        assert_eq!(circuit.press_until_high("b", 10), Some(1));
+        assert_eq!(circuit.first_high("output"), None);
+        assert_eq!(circuit.press_until_high("nope", 10), None);
+
+        let mut circuit: Circuit = COUNTERS.parse().unwrap();
+        assert_eq!(circuit.press_until_high("ia", 100), Some(3));
+        assert_eq!(circuit.press_until_high("ib", 100), Some(7));
+    }
+
+    #[test]
+    fn test_cycles() {
+        let circuit: Circuit = COUNTERS.parse().unwrap();
+        let id = |name| circuit.id(name).unwrap();
+        let parts = circuit.subcircuits();
+        assert_eq!(parts.len(), 2);
+        assert!(parts[0].contains(&id("ia")) && !parts[0].contains(&id("out")));
+
+        // the memory of `ib` differs from the start after the first press.
+        let (cycle, highs) = circuit.find_cycle(&parts[1]);
+        assert_eq!(
+            cycle,
+            Cycle {
+                start: 1,
+                length: 7
+            }
+        );
+        assert_eq!(highs[&id("ib")], [7]);
+
+        assert_eq!(circuit.presses_until_low("rx"), Some(21));
+        assert_eq!(circuit.presses_until_low("out"), None);
+    }
+
+    #[test]
+    fn test_dot() {
+        let circuit: Circuit = SIMPLE.parse().unwrap();
+        let dot = circuit.to_dot();
+        assert!(dot.starts_with("digraph circuit {\n"));
+        assert!(dot.contains("  \"inv\" [label=\"&inv\", shape=invhouse];\n"));
+        assert!(dot.contains("  \"c\" -> \"inv\";\n"));
+        assert!(dot.ends_with("}\n"));
+
+        assert!("%a b".parse::<Circuit>().is_err());
+        assert!("%a -> b".parse::<Circuit>().is_err());
+    }
+}
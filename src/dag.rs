@@ -0,0 +1,230 @@
+/// Directed acyclic graphs over the nodes `0..n`: topological order and dominator trees.
+use std::collections::VecDeque;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Dag {
+    successors: Vec<Vec<usize>>,
+    predecessors: Vec<Vec<usize>>,
+}
+
+impl Dag {
+    /// A graph with `n` nodes and no edges.
+    pub fn new(n: usize) -> Self {
+        Self {
+            successors: vec![vec![]; n],
+            predecessors: vec![vec![]; n],
+        }
+    }
+
+    pub fn from_edges(n: usize, edges: impl IntoIterator<Item = (usize, usize)>) -> Self {
+        let mut dag = Self::new(n);
+        for (from, to) in edges {
+            dag.add_edge(from, to);
+        }
+        dag
+    }
+
+    pub fn add_edge(&mut self, from: usize, to: usize) {
+        self.successors[from].push(to);
+        self.predecessors[to].push(from);
+    }
+
+    pub fn len(&self) -> usize {
+        self.successors.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.successors.is_empty()
+    }
+
+    pub fn successors(&self, node: usize) -> &[usize] {
+        &self.successors[node]
+    }
+
+    pub fn predecessors(&self, node: usize) -> &[usize] {
+        &self.predecessors[node]
+    }
+
+    /// Orders the nodes so that every edge points forward, with Kahn's algorithm.
+    /// Returns [`None`] if the graph has a cycle.
+    pub fn topological_sort(&self) -> Option<Vec<usize>> {
+        let mut in_degree: Vec<usize> = self.predecessors.iter().map(Vec::len).collect();
+        let mut queue: VecDeque<usize> = (0..self.len()).filter(|&i| in_degree[i] == 0).collect();
+        let mut order = Vec::with_capacity(self.len());
+        while let Some(node) = queue.pop_front() {
+            order.push(node);
+            for &next in &self.successors[node] {
+                in_degree[next] -= 1;
+                if in_degree[next] == 0 {
+                    queue.push_back(next);
+                }
+            }
+        }
+        (order.len() == self.len()).then_some(order)
+    }
+
+    /// Computes which nodes dominate which when starting from `root`: a node dominates another one
+    /// if every path from `root` to the other node passes through it.
+    ///
+    /// Uses the algorithm of Cooper, Harvey and Kennedy, which needs only a single pass
+    /// in topological order on an acyclic graph.
+    /// Panics if the graph has a cycle.
+    pub fn dominator_tree(&self, root: usize) -> DominatorTree {
+        let order = self.topological_sort().expect("graph is acyclic");
+        let mut position = vec![0; self.len()];
+        for (i, &node) in order.iter().enumerate() {
+            position[node] = i;
+        }
+
+        let mut idom: Vec<Option<usize>> = vec![None; self.len()];
+        let mut reachable = vec![false; self.len()];
+        reachable[root] = true;
+        for &node in order.iter().skip_while(|&&node| node != root).skip(1) {
+            let mut dominator = None;
+            for &pred in self.predecessors[node].iter().filter(|&&p| reachable[p]) {
+                dominator = Some(match dominator {
+                    None => pred,
+                    Some(mut other) => {
+                        // walk up the tree from the later node until both paths meet.
+                        let mut pred = pred;
+                        while pred != other {
+                            if position[pred] > position[other] {
+                                pred = idom[pred].unwrap();
+                            } else {
+                                other = idom[other].unwrap();
+                            }
+                        }
+                        pred
+                    }
+                });
+            }
+            if dominator.is_some() {
+                reachable[node] = true;
+                idom[node] = dominator;
+            }
+        }
+
+        let mut sizes: Vec<usize> = reachable.iter().map(|&r| usize::from(r)).collect();
+        for &node in order.iter().rev() {
+            if let Some(parent) = idom[node] {
+                sizes[parent] += sizes[node];
+            }
+        }
+
+        DominatorTree { root, idom, sizes }
+    }
+}
+
+/// The immediate dominators of all nodes that are reachable from the root, see [`Dag::dominator_tree`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DominatorTree {
+    root: usize,
+    idom: Vec<Option<usize>>,
+    sizes: Vec<usize>,
+}
+
+impl DominatorTree {
+    pub fn root(&self) -> usize {
+        self.root
+    }
+
+    /// The closest node that dominates `node`. [`None`] for the root and unreachable nodes.
+    pub fn immediate_dominator(&self, node: usize) -> Option<usize> {
+        self.idom[node]
+    }
+
+    /// All nodes that dominate `node` except itself, from the closest one up to the root.
+    pub fn dominators(&self, node: usize) -> impl Iterator<Item = usize> + '_ {
+        std::iter::successors(self.idom[node], |&n| self.idom[n])
+    }
+
+    pub fn dominates(&self, dominator: usize, node: usize) -> bool {
+        self.is_reachable(node)
+            && (dominator == node || self.dominators(node).any(|n| n == dominator))
+    }
+
+    pub fn is_reachable(&self, node: usize) -> bool {
+        self.sizes[node] > 0
+    }
+
+    /// The number of nodes that `node` dominates, including itself,
+    /// i.e. how many nodes become unreachable if `node` fails. 0 if `node` is unreachable.
+    pub fn subtree_size(&self, node: usize) -> usize {
+        self.sizes[node]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    //     0
+    //    / \
+    //   1   2
+    //    \ / \
+    //     3   4
+    //     |   |
+    //     5   6 <- 7
+    fn diamond() -> Dag {
+        Dag::from_edges(
+            8,
+            [
+                (0, 1),
+                (0, 2),
+                (1, 3),
+                (2, 3),
+                (2, 4),
+                (3, 5),
+                (4, 6),
+                (7, 6),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_topological_sort() {
+        let dag = diamond();
+        let order = dag.topological_sort().unwrap();
+        assert_eq!(order.len(), dag.len());
+        for from in 0..dag.len() {
+            for &to in dag.successors(from) {
+                let pos = |n| order.iter().position(|&x| x == n).unwrap();
+                assert!(pos(from) < pos(to));
+            }
+        }
+
+        let cyclic = Dag::from_edges(3, [(0, 1), (1, 2), (2, 1)]);
+        assert_eq!(cyclic.topological_sort(), None);
+    }
+
+    #[test]
+    fn test_dominator_tree() {
+        let tree = diamond().dominator_tree(0);
+        let idoms: Vec<_> = (0..8).map(|n| tree.immediate_dominator(n)).collect();
+        assert_eq!(
+            idoms,
+            [
+                None,
+                Some(0),
+                Some(0),
+                Some(0),
+                Some(2),
+                Some(3),
+                Some(4),
+                None
+            ]
+        );
+        assert_eq!(tree.dominators(6).collect::<Vec<_>>(), [4, 2, 0]);
+        assert!(tree.dominates(2, 6));
+        assert!(!tree.dominates(1, 3));
+        assert!(!tree.dominates(0, 7));
+
+        let sizes: Vec<_> = (0..8).map(|n| tree.subtree_size(n)).collect();
+        assert_eq!(sizes, [7, 1, 3, 2, 2, 1, 1, 0]);
+
+        // node 6 is reachable from 7 without passing 4.
+        let tree = diamond().dominator_tree(7);
+        assert_eq!(tree.subtree_size(7), 2);
+        assert!(!tree.is_reachable(4));
+    }
+}
@@ -81,11 +81,7 @@ impl Display for DayFromStrError {
 /* -------------------------------------------------------------------------- */
 
 /// An iterator that yields every day of advent from the 1st to the 25th.
-pub fn all_days() -> AllDays {
-    AllDays::new()
-}
-
-/// An iterator that yields every day of advent from the 1st to the 25th.
+/// [`all_days`](crate::all_days) yields them for every configured year.
 pub struct AllDays {
     current: u8,
 }
@@ -112,6 +108,30 @@ impl Iterator for AllDays {
     }
 }
 
+/// Parses a comma separated list of days and inclusive ranges, e.g. `3,5,10-15`.
+///
+/// ```
+/// # use advent_of_code::{day, parse_days};
+/// let days = parse_days("3,5,10-12").unwrap();
+/// assert_eq!(days, [day!(3), day!(5), day!(10), day!(11), day!(12)]);
+/// ```
+pub fn parse_days(s: &str) -> Result<Vec<Day>, DayFromStrError> {
+    let mut days = vec![];
+    for item in s.split(',').map(str::trim) {
+        match item.split_once('-') {
+            Some((start, end)) => {
+                let start: Day = start.trim().parse()?;
+                let end: Day = end.trim().parse()?;
+                days.extend((start.0..=end.0).map(Day));
+            }
+            None => days.push(item.parse()?),
+        }
+    }
+    days.sort_unstable();
+    days.dedup();
+    Ok(days)
+}
+
 /* -------------------------------------------------------------------------- */
 
 /// Creates a [`Day`] value in a const context.
@@ -134,11 +154,11 @@ macro_rules! day {
 
 #[cfg(feature = "test_lib")]
 mod tests {
-    use super::{all_days, Day};
+    use super::{parse_days, AllDays, Day};
 
     #[test]
     fn all_days_iterator() {
-        let mut iter = all_days();
+        let mut iter = AllDays::new();
 
         assert_eq!(iter.next(), Some(Day(1)));
         assert_eq!(iter.next(), Some(Day(2)));
@@ -167,6 +187,18 @@ mod tests {
         assert_eq!(iter.next(), Some(Day(25)));
         assert_eq!(iter.next(), None);
     }
+
+    #[test]
+    fn parses_day_lists() {
+        assert_eq!(
+            parse_days("10-12, 3,5,11").unwrap(),
+            vec![Day(3), Day(5), Day(10), Day(11), Day(12)]
+        );
+        assert_eq!(parse_days("25").unwrap(), vec![Day(25)]);
+        assert!(parse_days("12-10").unwrap().is_empty());
+        assert!(parse_days("0-3").is_err());
+        assert!(parse_days("3,").is_err());
+    }
 }
 
 /* -------------------------------------------------------------------------- */
@@ -0,0 +1,346 @@
+/// Polygons with integer vertices: exact area, lattice point counts and point-in-polygon tests.
+use std::ops::{Add, Div, Mul, Neg, Rem, Sub};
+
+/// Signed integers that can be used as coordinates of a [`Polygon`], e.g. `i64` or [`i256`](crate::i256::i256).
+pub trait Integer:
+    Copy
+    + Ord
+    + From<i8>
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Rem<Output = Self>
+    + Neg<Output = Self>
+{
+}
+impl<T> Integer for T where
+    T: Copy
+        + Ord
+        + From<i8>
+        + Add<Output = T>
+        + Sub<Output = T>
+        + Mul<Output = T>
+        + Div<Output = T>
+        + Rem<Output = T>
+        + Neg<Output = T>
+{
+}
+
+fn abs<T: Integer>(x: T) -> T {
+    if x < T::from(0) {
+        -x
+    } else {
+        x
+    }
+}
+
+fn gcd<T: Integer>(a: T, b: T) -> T {
+    if b == T::from(0) {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// A direction of a move on the lattice. Like the rows of a [`crate::Grid`], `Up` decreases `y`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+    ];
+
+    pub fn opposite(self) -> Self {
+        match self {
+            Direction::Up => Direction::Down,
+            Direction::Down => Direction::Up,
+            Direction::Left => Direction::Right,
+            Direction::Right => Direction::Left,
+        }
+    }
+
+    /// The point `length` steps from `(x, y)` in this direction.
+    pub fn step<T: Integer>(self, (x, y): (T, T), length: T) -> (T, T) {
+        match self {
+            Direction::Up => (x, y - length),
+            Direction::Down => (x, y + length),
+            Direction::Left => (x - length, y),
+            Direction::Right => (x + length, y),
+        }
+    }
+}
+
+/// Where a point lies relative to a [`Polygon`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Location {
+    Inside,
+    Boundary,
+    Outside,
+}
+
+/// A simple closed polygon, given by its `(x, y)` vertices in order.
+/// The last vertex is connected to the first one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Polygon<T> {
+    vertices: Vec<(T, T)>,
+}
+
+impl<T: Integer> Polygon<T> {
+    pub fn new(vertices: Vec<(T, T)>) -> Self {
+        Self { vertices }
+    }
+
+    /// Follows `moves` from the origin, e.g. the dig plan of a lagoon.
+    /// Returns [`None`] if the moves do not return to the origin.
+    pub fn from_moves(moves: impl IntoIterator<Item = (Direction, T)>) -> Option<Self> {
+        let mut position = (T::from(0), T::from(0));
+        let mut vertices = vec![position];
+        for (direction, length) in moves {
+            position = direction.step(position, length);
+            vertices.push(position);
+        }
+        if vertices.pop() != Some((T::from(0), T::from(0))) {
+            return None;
+        }
+        Some(Self { vertices })
+    }
+
+    /// Builds the polygon through the centers of a loop of grid cells, given as `(row, col)`
+    /// in the order they are visited, e.g. a pipe loop. Consecutive cells have to be adjacent.
+    /// Only the corners of the loop become vertices.
+    pub fn from_loop(cells: impl IntoIterator<Item = (usize, usize)>) -> Self
+    where
+        T: From<i32>,
+    {
+        let points: Vec<(T, T)> = cells
+            .into_iter()
+            .map(|(row, col)| {
+                let coordinate = |n: usize| T::from(i32::try_from(n).expect("grid is too large"));
+                (coordinate(col), coordinate(row))
+            })
+            .collect();
+
+        let n = points.len();
+        let vertices = (0..n)
+            .filter(|&i| {
+                let (prev, curr, next) = (points[(i + n - 1) % n], points[i], points[(i + 1) % n]);
+                cross(prev, curr, next) != T::from(0)
+            })
+            .map(|i| points[i])
+            .collect();
+        Self { vertices }
+    }
+
+    pub fn vertices(&self) -> &[(T, T)] {
+        &self.vertices
+    }
+
+    fn edges(&self) -> impl Iterator<Item = ((T, T), (T, T))> + '_ {
+        self.vertices
+            .iter()
+            .zip(self.vertices.iter().cycle().skip(1))
+            .map(|(&a, &b)| (a, b))
+    }
+
+    /// Twice the area, computed exactly with the shoelace formula.
+    /// It is positive if the vertices are in clockwise order in a `y` down system
+    /// (counterclockwise if `y` points up) and negative otherwise.
+    ///
+    /// <https://en.wikipedia.org/wiki/Shoelace_formula>
+    pub fn double_signed_area(&self) -> T {
+        self.edges()
+            .fold(T::from(0), |sum, (a, b)| sum + (a.0 * b.1 - b.0 * a.1))
+    }
+
+    /// The area, rounded down if it is not an integer,
+    /// which is only possible with edges that are not axis-aligned.
+    pub fn area(&self) -> T {
+        abs(self.double_signed_area()) / T::from(2)
+    }
+
+    /// The number of lattice points on the edges of the polygon.
+    pub fn boundary_points(&self) -> T {
+        self.edges().fold(T::from(0), |sum, (a, b)| {
+            sum + gcd(abs(b.0 - a.0), abs(b.1 - a.1))
+        })
+    }
+
+    /// The number of lattice points strictly inside the polygon, computed with Pick's theorem.
+    ///
+    /// <https://en.wikipedia.org/wiki/Pick%27s_theorem>
+    pub fn interior_points(&self) -> T {
+        (abs(self.double_signed_area()) - self.boundary_points() + T::from(2)) / T::from(2)
+    }
+
+    /// The number of lattice points inside or on the boundary of the polygon,
+    /// e.g. the cubic meters of a lagoon that is dug along the edges.
+    pub fn lattice_points(&self) -> T {
+        self.interior_points() + self.boundary_points()
+    }
+
+    /// Locates a point with a ray casting test.
+    pub fn locate(&self, point: (T, T)) -> Location {
+        let zero = T::from(0);
+        let mut inside = false;
+        for (a, b) in self.edges() {
+            if cross(a, b, point) == zero
+                && point.0 >= a.0.min(b.0)
+                && point.0 <= a.0.max(b.0)
+                && point.1 >= a.1.min(b.1)
+                && point.1 <= a.1.max(b.1)
+            {
+                return Location::Boundary;
+            }
+
+            // does a ray from the point towards +x cross the edge?
+            if (a.1 > point.1) != (b.1 > point.1) {
+                let lhs = (point.0 - a.0) * (b.1 - a.1);
+                let rhs = (point.1 - a.1) * (b.0 - a.0);
+                if (b.1 > a.1 && lhs < rhs) || (b.1 < a.1 && lhs > rhs) {
+                    inside = !inside;
+                }
+            }
+        }
+
+        if inside {
+            Location::Inside
+        } else {
+            Location::Outside
+        }
+    }
+
+    /// Whether the point lies inside or on the boundary of the polygon.
+    pub fn contains(&self, point: (T, T)) -> bool {
+        self.locate(point) != Location::Outside
+    }
+}
+
+/// The z component of the cross product of `b - a` and `c - a`, zero if the points are collinear.
+fn cross<T: Integer>(a: (T, T), b: (T, T), c: (T, T)) -> T {
+    (b.0 - a.0) * (c.1 - a.1) - (b.1 - a.1) * (c.0 - a.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::i256::i256;
+
+    /// The dig plan of the example of 2023 day 18.
+    fn lagoon() -> Vec<(Direction, i64)> {
+        use Direction::*;
+        vec![
+            (Right, 6),
+            (Down, 5),
+            (Left, 2),
+            (Down, 2),
+            (Right, 2),
+            (Down, 2),
+            (Left, 5),
+            (Up, 2),
+            (Left, 1),
+            (Up, 2),
+            (Right, 2),
+            (Up, 3),
+            (Left, 2),
+            (Up, 2),
+        ]
+    }
+
+    #[test]
+    fn test_area() {
+        let square = Polygon::new(vec![(0, 0), (4, 0), (4, 4), (0, 4)]);
+        assert_eq!(square.double_signed_area(), 32);
+        assert_eq!(square.area(), 16);
+        let reversed = Polygon::new(square.vertices().iter().rev().copied().collect());
+        assert_eq!(reversed.double_signed_area(), -32);
+        assert_eq!(reversed.area(), 16);
+
+        let triangle = Polygon::new(vec![(0, 0), (3, 0), (0, 3)]);
+        assert_eq!(triangle.double_signed_area(), 9);
+        assert_eq!(triangle.area(), 4);
+    }
+
+    #[test]
+    fn test_lattice_points() {
+        let triangle = Polygon::new(vec![(0, 0), (4, 0), (0, 4)]);
+        assert_eq!(triangle.boundary_points(), 12);
+        assert_eq!(triangle.interior_points(), 3);
+        assert_eq!(triangle.lattice_points(), 15);
+
+        let lagoon = Polygon::from_moves(lagoon()).unwrap();
+        assert_eq!(lagoon.boundary_points(), 38);
+        assert_eq!(lagoon.lattice_points(), 62);
+
+        let open = [
+            (Direction::Right, 2),
+            (Direction::Down, 2),
+            (Direction::Left, 2),
+        ];
+        assert_eq!(Polygon::from_moves(open), None);
+    }
+
+    #[test]
+    fn test_i256() {
+        let big = i256::from(1_i64 << 62);
+        let square = Polygon::new(vec![
+            (i256::ZERO, i256::ZERO),
+            (big, i256::ZERO),
+            (big, big),
+            (i256::ZERO, big),
+        ]);
+        assert_eq!(square.area().to_string(), (big * big).to_string());
+        assert_eq!(square.boundary_points(), big * i256::from(4));
+        assert_eq!(
+            square.lattice_points(),
+            (big + i256::ONE) * (big + i256::ONE)
+        );
+    }
+
+    #[test]
+    fn test_locate() {
+        let lagoon = Polygon::from_moves(lagoon()).unwrap();
+        assert_eq!(lagoon.locate((1, 1)), Location::Inside);
+        assert_eq!(lagoon.locate((0, 0)), Location::Boundary);
+        assert_eq!(lagoon.locate((3, 0)), Location::Boundary);
+        assert_eq!(lagoon.locate((0, 3)), Location::Outside);
+        assert_eq!(lagoon.locate((7, 1)), Location::Outside);
+        assert!(lagoon.contains((6, 5)));
+        assert!(!lagoon.contains((-1, 5)));
+
+        let diamond = Polygon::new(vec![(0, -2), (2, 0), (0, 2), (-2, 0)]);
+        assert_eq!(diamond.locate((0, 0)), Location::Inside);
+        assert_eq!(diamond.locate((1, 1)), Location::Boundary);
+        assert_eq!(diamond.locate((2, 2)), Location::Outside);
+        assert_eq!(diamond.locate((0, 3)), Location::Outside);
+    }
+
+    #[test]
+    fn test_from_loop() {
+        // a 3x3 ring of cells, walked clockwise from the top left corner.
+        let ring = [
+            (0, 0),
+            (0, 1),
+            (0, 2),
+            (1, 2),
+            (2, 2),
+            (2, 1),
+            (2, 0),
+            (1, 0),
+        ];
+        let polygon = Polygon::<i64>::from_loop(ring);
+        assert_eq!(polygon.vertices(), [(0, 0), (2, 0), (2, 2), (0, 2)]);
+        assert_eq!(polygon.interior_points(), 1);
+        assert_eq!(polygon.boundary_points(), 8);
+        assert_eq!(polygon.locate((1, 1)), Location::Inside);
+    }
+}
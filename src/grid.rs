@@ -1,3 +1,4 @@
+use std::collections::VecDeque;
 use std::fmt::Debug;
 use std::fmt::Write;
 use std::ops::Deref;
@@ -284,6 +285,142 @@ impl<T> Grid<T> {
         }
     }
 }
+/// Which neighbours of a cell are adjacent to it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Connectivity {
+    /// Only the cells that share an edge.
+    Four,
+    /// The cells that share an edge or a corner.
+    Eight,
+}
+/// The shape of a connected region of a grid.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Region {
+    pub area: usize,
+    /// The number of cell edges between the region and other cells or the border of the grid.
+    pub perimeter: usize,
+    /// The number of straight sides of the fence around the region, including those of holes.
+    pub sides: usize,
+}
+/// The connected regions of a grid, see [`Grid::components`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Components {
+    /// The label of the region of every cell, labels count up from 0 in row-major order.
+    pub labels: Grid<usize>,
+    /// The shape of every region, indexed by label.
+    pub regions: Vec<Region>,
+}
+impl<T> Grid<T> {
+    fn neighbour_indices(&self, index: usize, connectivity: Connectivity) -> Vec<usize>
+    where
+        T: Clone,
+    {
+        match connectivity {
+            Connectivity::Four => self
+                .neighbours4_with_index(index)
+                .into_iter()
+                .map(|(i, _)| i.to_flat_index(self))
+                .collect(),
+            Connectivity::Eight => self
+                .neighbours8_with_index(index)
+                .into_iter()
+                .map(|(_, i)| i.to_flat_index(self))
+                .collect(),
+        }
+    }
+    /// Returns the flat indices of all cells that are reachable from `start`
+    /// over cells that satisfy `predicate`, in the order they are found.
+    /// It is empty if `start` does not satisfy `predicate`.
+    pub fn flood_fill(
+        &self,
+        start: impl OwnIndex<T>,
+        connectivity: Connectivity,
+        predicate: impl Fn(&T) -> bool,
+    ) -> Vec<usize>
+    where
+        T: Clone,
+    {
+        let start = start.to_flat_index(self);
+        if !predicate(&self[start]) {
+            return vec![];
+        }
+        let mut visited = vec![false; self.len()];
+        visited[start] = true;
+        let mut filled = vec![start];
+        let mut queue = VecDeque::from([start]);
+        while let Some(curr) = queue.pop_front() {
+            for next in self.neighbour_indices(curr, connectivity) {
+                if !visited[next] && predicate(&self[next]) {
+                    visited[next] = true;
+                    filled.push(next);
+                    queue.push_back(next);
+                }
+            }
+        }
+        filled
+    }
+    /// Labels the connected regions of the grid. Adjacent cells belong to the same region
+    /// if `same_region` returns true for their values, e.g. `|a, b| a == b` for plots of the same plant.
+    ///
+    /// The perimeter and the sides of a region are always measured along the edges of its cells,
+    /// also with [`Connectivity::Eight`].
+    pub fn components(
+        &self,
+        connectivity: Connectivity,
+        same_region: impl Fn(&T, &T) -> bool,
+    ) -> Components
+    where
+        T: Clone,
+    {
+        let mut labels = Grid::from_iter(std::iter::repeat_n(usize::MAX, self.len()), self.cols);
+        let mut regions = vec![];
+        for start in 0..self.len() {
+            if labels[start] != usize::MAX {
+                continue;
+            }
+            let label = regions.len();
+            labels[start] = label;
+            let mut queue = VecDeque::from([start]);
+            while let Some(curr) = queue.pop_front() {
+                for next in self.neighbour_indices(curr, connectivity) {
+                    if labels[next] == usize::MAX && same_region(&self[curr], &self[next]) {
+                        labels[next] = label;
+                        queue.push_back(next);
+                    }
+                }
+            }
+            regions.push(Region::default());
+        }
+
+        for index in 0..labels.len() {
+            let (y, x) = index.to_2d_index(&labels);
+            let label = labels[index];
+            let inside = |dy: isize, dx: isize| {
+                let y = y.checked_add_signed(dy)?;
+                let x = x.checked_add_signed(dx)?;
+                labels.get((y, x)).filter(|&&l| l == label)
+            };
+            let region = &mut regions[label];
+            region.area += 1;
+            for (dy, dx) in [(-1, 0), (0, 1), (1, 0), (0, -1)] {
+                if inside(dy, dx).is_none() {
+                    region.perimeter += 1;
+                }
+            }
+            // every corner of the fence starts a new side.
+            for (dy, dx) in [(-1, -1), (-1, 1), (1, -1), (1, 1)] {
+                let vertical = inside(dy, 0).is_some();
+                let horizontal = inside(0, dx).is_some();
+                let diagonal = inside(dy, dx).is_some();
+                if (!vertical && !horizontal) || (vertical && horizontal && !diagonal) {
+                    region.sides += 1;
+                }
+            }
+        }
+
+        Components { labels, regions }
+    }
+}
 impl<T> Index<(usize, usize)> for Grid<T> {
     type Output = T;
 
@@ -383,3 +520,88 @@ where
         Grid::from_iter(self.into_iter().map(|v| -v), n)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn garden(input: &str) -> Grid<char> {
+        Grid::from_iter_iter(input.lines().map(|l| l.chars()))
+    }
+
+    #[test]
+    fn test_flood_fill() {
+        let grid = garden("..#\n.#.\n#..");
+        let mut four = grid.flood_fill(0, Connectivity::Four, |&c| c == '.');
+        four.sort();
+        assert_eq!(four, [0, 1, 3]);
+        let mut eight = grid.flood_fill((2, 2), Connectivity::Eight, |&c| c == '#');
+        assert!(eight.is_empty());
+        eight = grid.flood_fill((0, 2), Connectivity::Eight, |&c| c == '#');
+        eight.sort();
+        assert_eq!(eight, [2, 4, 6]);
+    }
+
+    #[test]
+    fn test_components() {
+        let grid = garden("AAAA\nBBCD\nBBCC\nEEEC");
+        let components = grid.components(Connectivity::Four, |a, b| a == b);
+        assert_eq!(components.labels.get_row(1), [1, 1, 2, 3]);
+        assert_eq!(
+            components.regions,
+            [
+                Region {
+                    area: 4,
+                    perimeter: 10,
+                    sides: 4
+                },
+                Region {
+                    area: 4,
+                    perimeter: 8,
+                    sides: 4
+                },
+                Region {
+                    area: 4,
+                    perimeter: 10,
+                    sides: 8
+                },
+                Region {
+                    area: 1,
+                    perimeter: 4,
+                    sides: 4
+                },
+                Region {
+                    area: 3,
+                    perimeter: 8,
+                    sides: 4
+                },
+            ]
+        );
+
+        // the inner region is a hole in the outer one, which adds to its sides.
+        let grid = garden("OOOOO\nOXOXO\nOOOOO\nOXOXO\nOOOOO");
+        let components = grid.components(Connectivity::Four, |a, b| a == b);
+        assert_eq!(components.regions.len(), 5);
+        assert_eq!(
+            components.regions[0],
+            Region {
+                area: 21,
+                perimeter: 36,
+                sides: 20
+            }
+        );
+
+        let components = grid.components(Connectivity::Eight, |a, b| a == b);
+        assert_eq!(components.regions.len(), 5);
+        let diagonal = garden("X.\n.X").components(Connectivity::Eight, |a, b| a == b);
+        assert_eq!(diagonal.regions.len(), 2);
+        assert_eq!(
+            diagonal.regions[0],
+            Region {
+                area: 2,
+                perimeter: 8,
+                sides: 8
+            }
+        );
+    }
+}
@@ -0,0 +1,218 @@
+/// Closed loops through a [`Grid`], e.g. a loop of pipes, and the cells they enclose.
+use crate::geometry::Direction;
+use crate::grid::Connectivity;
+use crate::{Grid, OwnIndex};
+
+/// The neighbour of `(row, col)` in `direction`, if it lies within a `rows` x `cols` grid.
+fn step(
+    (row, col): (usize, usize),
+    direction: Direction,
+    (rows, cols): (usize, usize),
+) -> Option<(usize, usize)> {
+    match direction {
+        Direction::Up => Some((row.checked_sub(1)?, col)),
+        Direction::Down => (row + 1 < rows).then_some((row + 1, col)),
+        Direction::Left => Some((row, col.checked_sub(1)?)),
+        Direction::Right => (col + 1 < cols).then_some((row, col + 1)),
+    }
+}
+
+/// The direction from a cell to an adjacent one.
+fn direction(from: (usize, usize), to: (usize, usize)) -> Direction {
+    match (
+        to.0 as isize - from.0 as isize,
+        to.1 as isize - from.1 as isize,
+    ) {
+        (-1, 0) => Direction::Up,
+        (1, 0) => Direction::Down,
+        (0, -1) => Direction::Left,
+        (0, 1) => Direction::Right,
+        _ => unreachable!("cells of a loop are adjacent"),
+    }
+}
+
+/// A closed loop of cells, every cell is connected to the previous and the next one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GridLoop {
+    /// The `(row, col)` of every cell in the order they are visited, starting at the start cell.
+    cells: Vec<(usize, usize)>,
+    rows: usize,
+    cols: usize,
+}
+
+impl<T> Grid<T> {
+    /// Follows the loop that passes through `start`. `connections` returns the directions
+    /// a cell connects to, e.g. `[Up, Down]` for a `|` pipe. The shape of the start cell is unknown,
+    /// it is inferred from the neighbours that connect back to it.
+    ///
+    /// Returns [`None`] if no loop passes through `start`.
+    pub fn trace_loop<'a>(
+        &self,
+        start: impl OwnIndex<T>,
+        connections: impl Fn(&T) -> &'a [Direction],
+    ) -> Option<GridLoop> {
+        let start = start.to_2d_index(self);
+        let size = (self.height(), self.width());
+
+        Direction::ALL.into_iter().find_map(|first| {
+            let mut cells = vec![start];
+            let (mut position, mut heading) = (start, first);
+            loop {
+                let next = step(position, heading, size)?;
+                if next == start {
+                    return Some(GridLoop {
+                        cells,
+                        rows: size.0,
+                        cols: size.1,
+                    });
+                }
+                let connected = connections(&self[next]);
+                if !connected.contains(&heading.opposite()) || cells.len() > self.len() {
+                    return None;
+                }
+                heading = *connected.iter().find(|&&d| d != heading.opposite())?;
+                position = next;
+                cells.push(next);
+            }
+        })
+    }
+}
+
+impl GridLoop {
+    /// The `(row, col)` of every cell in the order they are visited, starting at the start cell.
+    pub fn cells(&self) -> &[(usize, usize)] {
+        &self.cells
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// The directions the `i`-th cell of the loop connects to, towards the previous and the next cell.
+    pub fn connections(&self, i: usize) -> [Direction; 2] {
+        let n = self.len();
+        let cell = self.cells[i];
+        [
+            direction(cell, self.cells[(i + n - 1) % n]),
+            direction(cell, self.cells[(i + 1) % n]),
+        ]
+    }
+
+    /// The shape of the start cell, inferred from its neighbours on the loop.
+    pub fn start_shape(&self) -> [Direction; 2] {
+        self.connections(0)
+    }
+
+    /// The cells of the loop as a mask over the grid, with whether each cell connects upwards.
+    fn mask(&self) -> Grid<Option<bool>> {
+        let mut mask = Grid::from_iter(std::iter::repeat_n(None, self.rows * self.cols), self.cols);
+        for (i, &cell) in self.cells.iter().enumerate() {
+            mask[cell] = Some(self.connections(i).contains(&Direction::Up));
+        }
+        mask
+    }
+
+    /// The cells enclosed by the loop in row-major order, found with a scan line per row:
+    /// crossing a loop cell that connects upwards switches between outside and inside.
+    pub fn enclosed_by_parity(&self) -> Vec<(usize, usize)> {
+        let mask = self.mask();
+        let mut enclosed = vec![];
+        for row in 0..self.rows {
+            let mut inside = false;
+            for col in 0..self.cols {
+                match mask[(row, col)] {
+                    None if inside => enclosed.push((row, col)),
+                    Some(true) => inside = !inside,
+                    _ => {}
+                }
+            }
+        }
+        enclosed
+    }
+
+    /// The cells enclosed by the loop in row-major order, found by flooding the outside
+    /// of a grid upscaled by two, so that the flood can squeeze between adjacent pipes
+    /// that are not connected.
+    pub fn enclosed_by_flood_fill(&self) -> Vec<(usize, usize)> {
+        let (rows, cols) = (2 * self.rows + 1, 2 * self.cols + 1);
+        let mut blocked = Grid::from_iter(std::iter::repeat_n(false, rows * cols), cols);
+        for (i, &(row, col)) in self.cells.iter().enumerate() {
+            let (next_row, next_col) = self.cells[(i + 1) % self.len()];
+            blocked[(2 * row + 1, 2 * col + 1)] = true;
+            blocked[(row + next_row + 1, col + next_col + 1)] = true;
+        }
+
+        let mut outside = blocked.clone();
+        for i in blocked.flood_fill(0, Connectivity::Four, |&b| !b) {
+            outside[i] = true;
+        }
+
+        (0..self.rows)
+            .flat_map(|row| (0..self.cols).map(move |col| (row, col)))
+            .filter(|&(row, col)| !outside[(2 * row + 1, 2 * col + 1)])
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Direction::*;
+
+    fn pipes(c: &char) -> &'static [Direction] {
+        match c {
+            '|' => &[Up, Down],
+            '-' => &[Left, Right],
+            'L' => &[Up, Right],
+            'J' => &[Up, Left],
+            '7' => &[Down, Left],
+            'F' => &[Down, Right],
+            _ => &[],
+        }
+    }
+
+    fn trace(input: &str) -> GridLoop {
+        let grid = Grid::from_iter_iter(input.lines().map(|l| l.chars()));
+        let start = grid.iter().position(|&c| c == 'S').unwrap();
+        grid.trace_loop(start, pipes).unwrap()
+    }
+
+    #[test]
+    fn test_trace_loop() {
+        let pipe_loop = trace("-L|F7\n7S-7|\nL|7||\n-L-J|\nL|-JF");
+        assert_eq!(pipe_loop.len(), 8);
+        assert_eq!(pipe_loop.cells()[0], (1, 1));
+        assert_eq!(pipe_loop.start_shape(), [Down, Right]);
+        assert_eq!(pipe_loop.enclosed_by_parity(), [(2, 2)]);
+        assert_eq!(pipe_loop.enclosed_by_flood_fill(), [(2, 2)]);
+
+        let grid = Grid::from_iter_iter("S-7\n|.|\nL-.".lines().map(|l| l.chars()));
+        assert_eq!(grid.trace_loop(0, pipes), None);
+    }
+
+    #[test]
+    fn test_enclosed() {
+        let squeezed = trace(
+            "..........\n.S------7.\n.|F----7|.\n.||....||.\n.||....||.\n.|L-7F-J|.\n.|..||..|.\n.L--JL--J.\n..........",
+        );
+        assert_eq!(
+            squeezed.enclosed_by_parity(),
+            [(6, 2), (6, 3), (6, 6), (6, 7)]
+        );
+        assert_eq!(
+            squeezed.enclosed_by_flood_fill(),
+            squeezed.enclosed_by_parity()
+        );
+
+        let junk = trace(
+            "FF7FSF7F7F7F7F7F---7\nL|LJ||||||||||||F--J\nFL-7LJLJ||||||LJL-77\nF--JF--7||LJLJ7F7FJ-\nL---JF-JLJ.||-FJLJJ7\n|F|F-JF---7F7-L7L|7|\n|FFJF7L7F-JF7|JL---7\n7-L-JL7||F7|L7F-7F7|\nL.L7LFJ|||||FJL7||LJ\nL7JLJL-JLJLJL--JLJ.L",
+        );
+        assert_eq!(junk.start_shape(), [Left, Down]);
+        assert_eq!(junk.enclosed_by_parity().len(), 10);
+        assert_eq!(junk.enclosed_by_flood_fill(), junk.enclosed_by_parity());
+    }
+}
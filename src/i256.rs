@@ -355,6 +355,29 @@ impl std::ops::Div for i256 {
         self.checked_div(rhs).unwrap()
     }
 }
+impl std::ops::Rem for i256 {
+    type Output = Self;
+
+    /// The remainder of a truncating division, it has the sign of `self` like for primitive integers.
+    fn rem(self, rhs: Self) -> Self::Output {
+        if self == i256::ZERO {
+            return i256::ZERO;
+        }
+        let (_, r) = self
+            .unsigned_abs()
+            .expect("not yet implemented for i256::MIN")
+            .checked_unsigned_div_and_rem(
+                rhs.unsigned_abs()
+                    .expect("not yet implemented for i256::MIN"),
+            )
+            .expect("division by zero");
+        if self.is_negative() {
+            -r
+        } else {
+            r
+        }
+    }
+}
 impl std::ops::Neg for i256 {
     type Output = Self;
 
@@ -610,6 +633,14 @@ mod tests {
         assert_eq!(i256::MAX.wrapping_sub(i256::MIN), (i256::from(-1)));
     }
     #[test]
+    fn test_rem_operator() {
+        assert_eq!(i256::from(14) % i256::from(5), i256::from(4));
+        assert_eq!(i256::from(-14) % i256::from(5), i256::from(-4));
+        assert_eq!(i256::from(14) % i256::from(-5), i256::from(4));
+        assert_eq!(i256::from(0) % i256::from(5), i256::from(0));
+        assert_eq!(i256::from(5) % i256::from(5), i256::from(0));
+    }
+    #[test]
     fn test_checked_div() {
         assert_eq!(
             i256::from(10).checked_div(i256::from(5)),
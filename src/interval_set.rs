@@ -0,0 +1,141 @@
+/// Sets of integers stored as sorted, disjoint and non-adjacent half-open ranges.
+use std::ops::Range;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct IntervalSet {
+    ranges: Vec<Range<u64>>,
+}
+
+impl IntervalSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn ranges(&self) -> &[Range<u64>] {
+        &self.ranges
+    }
+
+    /// The number of integers in the set.
+    pub fn len(&self) -> u64 {
+        self.ranges.iter().map(|r| r.end - r.start).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    pub fn contains(&self, value: u64) -> bool {
+        let i = self.ranges.partition_point(|r| r.end <= value);
+        self.ranges.get(i).is_some_and(|r| r.start <= value)
+    }
+
+    pub fn min(&self) -> Option<u64> {
+        self.ranges.first().map(|r| r.start)
+    }
+
+    pub fn insert(&mut self, range: Range<u64>) {
+        if range.is_empty() {
+            return;
+        }
+        // the ranges that overlap or touch `range` are merged into it.
+        let first = self.ranges.partition_point(|r| r.end < range.start);
+        let last = self.ranges.partition_point(|r| r.start <= range.end);
+        let merged = if first < last {
+            self.ranges[first].start.min(range.start)..self.ranges[last - 1].end.max(range.end)
+        } else {
+            range
+        };
+        self.ranges.splice(first..last, [merged]);
+    }
+
+    pub fn remove(&mut self, range: Range<u64>) {
+        if range.is_empty() {
+            return;
+        }
+        let first = self.ranges.partition_point(|r| r.end <= range.start);
+        let last = self.ranges.partition_point(|r| r.start < range.end);
+        if first >= last {
+            return;
+        }
+        let before = self.ranges[first].start..range.start;
+        let after = range.end..self.ranges[last - 1].end;
+        let rest = [before, after].into_iter().filter(|r| !r.is_empty());
+        self.ranges.splice(first..last, rest);
+    }
+
+    /// The integers that are in the set and in `range`.
+    pub fn intersection(&self, range: Range<u64>) -> Self {
+        Self {
+            ranges: self
+                .ranges
+                .iter()
+                .map(|r| r.start.max(range.start)..r.end.min(range.end))
+                .filter(|r| !r.is_empty())
+                .collect(),
+        }
+    }
+
+    /// The integers that are in the set but not in `range`.
+    pub fn difference(&self, range: Range<u64>) -> Self {
+        let mut set = self.clone();
+        set.remove(range);
+        set
+    }
+}
+
+impl From<Range<u64>> for IntervalSet {
+    fn from(range: Range<u64>) -> Self {
+        let mut set = Self::new();
+        set.insert(range);
+        set
+    }
+}
+
+impl FromIterator<Range<u64>> for IntervalSet {
+    fn from_iter<I: IntoIterator<Item = Range<u64>>>(iter: I) -> Self {
+        let mut set = Self::new();
+        iter.into_iter().for_each(|r| set.insert(r));
+        set
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert() {
+        let mut set: IntervalSet = [10..20, 0..5, 30..40].into_iter().collect();
+        assert_eq!(set.ranges(), [0..5, 10..20, 30..40]);
+        set.insert(5..10);
+        assert_eq!(set.ranges(), [0..20, 30..40]);
+        set.insert(15..35);
+        assert_eq!(set, IntervalSet::from(0..40));
+        set.insert(50..50);
+        assert_eq!(set.len(), 40);
+        assert!(set.contains(39) && !set.contains(40));
+    }
+
+    #[test]
+    fn test_remove() {
+        let mut set: IntervalSet = [0..10, 20..30].into_iter().collect();
+        set.remove(5..25);
+        assert_eq!(set.ranges(), [0..5, 25..30]);
+        set.remove(0..1);
+        set.remove(40..50);
+        assert_eq!(set.ranges(), [1..5, 25..30]);
+        set.remove(2..3);
+        assert_eq!(set.ranges(), [1..2, 3..5, 25..30]);
+        assert_eq!(set.min(), Some(1));
+        set.remove(0..100);
+        assert!(set.is_empty());
+    }
+
+    #[test]
+    fn test_intersection() {
+        let set: IntervalSet = [0..10, 20..30].into_iter().collect();
+        assert_eq!(set.intersection(5..25).ranges(), [5..10, 20..25]);
+        assert_eq!(set.difference(5..25).ranges(), [0..5, 25..30]);
+        assert!(set.intersection(10..20).is_empty());
+    }
+}
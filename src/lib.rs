@@ -1,15 +1,36 @@
 mod day;
+pub mod geometry;
 pub mod grid;
+pub mod grid_loop;
+mod puzzle;
+mod year;
+pub use geometry::Polygon;
 pub use grid::Grid;
 pub use grid::OwnIndex;
+pub use grid::{Components, Connectivity, Region};
+pub use grid_loop::GridLoop;
 pub mod template;
 pub use day::*;
+pub use puzzle::*;
+pub use year::*;
 mod range_ext;
 pub use range_ext::*;
 mod math;
 pub use math::*;
 pub mod bitmask;
+pub mod circuit;
+pub mod dag;
 pub use bitmask::*;
+pub use dag::{Dag, DominatorTree};
 pub mod nonnegative;
+pub mod stacking;
 pub use nonnegative::*;
 pub mod i256;
+pub mod interval_set;
+pub use interval_set::IntervalSet;
+pub mod piecewise_map;
+pub use piecewise_map::PiecewiseMap;
+pub mod memo;
+pub use memo::{Cache, DenseMemo, Memo};
+pub mod nonogram;
+pub mod rules;
@@ -2,54 +2,138 @@ use advent_of_code::template::commands::{all, download, read, scaffold, solve};
 use args::{parse, AppArguments};
 
 mod args {
-    use std::process;
+    use std::{process, time::Duration};
 
-    use advent_of_code::Day;
+    use advent_of_code::{
+        parse_days,
+        template::{
+            commands::{all::Filter, solve::SolveOptions},
+            config::{self, LimitsConfig},
+            generate::Synthetic,
+        },
+        Puzzle, Year,
+    };
 
     pub enum AppArguments {
         Download {
-            day: Day,
+            puzzle: Puzzle,
         },
         Read {
-            day: Day,
+            puzzle: Puzzle,
         },
         Scaffold {
-            day: Day,
+            puzzle: Puzzle,
+            template: Option<String>,
+            answer_type: Option<String>,
+            force: bool,
         },
         Solve {
-            day: Day,
-            release: bool,
-            time: bool,
-            submit: Option<u8>,
+            puzzle: Puzzle,
+            options: SolveOptions,
         },
         All {
+            year: Option<Year>,
+            limits: LimitsConfig,
+            jobs: usize,
+            filter: Filter,
+            synthetic: Option<Synthetic>,
             release: bool,
             time: bool,
         },
     }
 
+    /// Parses the day and the optional `--year` of a command into a [`Puzzle`].
+    fn puzzle(args: &mut pico_args::Arguments) -> Result<Puzzle, pico_args::Error> {
+        let year = args.opt_value_from_str("--year")?;
+        Ok(config::get().puzzle(year, args.free_from_str()?))
+    }
+
+    /// Reads the limits of `aoc.toml`, overridden by `--timeout`, `--part-timeout` and `--memory`.
+    fn limits(args: &mut pico_args::Arguments) -> Result<LimitsConfig, pico_args::Error> {
+        let mut limits = config::get().limits.clone();
+        if let Some(secs) = args.opt_value_from_str("--timeout")? {
+            limits.day_timeout = Some(Duration::from_secs(secs));
+        }
+        if let Some(secs) = args.opt_value_from_str("--part-timeout")? {
+            limits.part_timeout = Some(Duration::from_secs(secs));
+        }
+        limits.memory |= args.contains("--memory");
+        Ok(limits)
+    }
+
+    fn parse_part(s: &str) -> Result<u8, String> {
+        match s {
+            "1" => Ok(1),
+            "2" => Ok(2),
+            _ => Err("expecting part 1 or 2".into()),
+        }
+    }
+
+    /// Parses `--scale <n>[,<n>...]` and `--seed <n>` into one generated input per scale.
+    fn synthetic(args: &mut pico_args::Arguments) -> Result<Vec<Synthetic>, pico_args::Error> {
+        let scales: Option<Vec<usize>> = args.opt_value_from_fn("--scale", |s| {
+            s.split(',')
+                .map(|scale| scale.trim().parse::<usize>())
+                .collect::<Result<_, _>>()
+        })?;
+        let seed = args.opt_value_from_str("--seed")?;
+        Ok(scales
+            .unwrap_or_default()
+            .into_iter()
+            .map(|scale| Synthetic { scale, seed })
+            .collect())
+    }
+
     pub fn parse() -> Result<AppArguments, Box<dyn std::error::Error>> {
         let mut args = pico_args::Arguments::from_env();
 
         let app_args = match args.subcommand()?.as_deref() {
             Some("all") => AppArguments::All {
+                year: args.opt_value_from_str("--year")?,
+                limits: limits(&mut args)?,
+                jobs: args.opt_value_from_str(["-j", "--jobs"])?.unwrap_or(1),
+                filter: Filter {
+                    days: args.opt_value_from_fn("--days", parse_days)?,
+                    skip: args
+                        .opt_value_from_fn("--skip", parse_days)?
+                        .unwrap_or_default(),
+                    part: args.opt_value_from_fn("--part", parse_part)?,
+                    only_solved: args.contains("--only-solved"),
+                    only_failing: args.contains("--only-failing"),
+                },
+                synthetic: match synthetic(&mut args)?.as_slice() {
+                    [] => None,
+                    [synthetic] => Some(*synthetic),
+                    _ => {
+                        eprintln!("`cargo all` runs a single `--scale` at a time.");
+                        process::exit(1);
+                    }
+                },
                 release: args.contains("--release"),
                 time: args.contains("--time"),
             },
             Some("download") => AppArguments::Download {
-                day: args.free_from_str()?,
+                puzzle: puzzle(&mut args)?,
             },
             Some("read") => AppArguments::Read {
-                day: args.free_from_str()?,
+                puzzle: puzzle(&mut args)?,
             },
             Some("scaffold") => AppArguments::Scaffold {
-                day: args.free_from_str()?,
+                puzzle: puzzle(&mut args)?,
+                template: args.opt_value_from_str("--template")?,
+                answer_type: args.opt_value_from_str("--type")?,
+                force: args.contains("--force"),
             },
             Some("solve") => AppArguments::Solve {
-                day: args.free_from_str()?,
-                release: args.contains("--release"),
-                submit: args.opt_value_from_str("--submit")?,
-                time: args.contains("--time"),
+                puzzle: puzzle(&mut args)?,
+                options: SolveOptions {
+                    release: args.contains("--release"),
+                    submit: args.opt_value_from_str("--submit")?,
+                    time: args.contains("--time"),
+                    profile: args.contains("--profile"),
+                    synthetic: synthetic(&mut args)?,
+                    watch: args.contains("--watch"),
+                },
             },
             Some(x) => {
                 eprintln!("Unknown command: {x}");
@@ -77,16 +161,24 @@ fn main() {
             std::process::exit(1);
         }
         Ok(args) => match args {
-            AppArguments::All { release, time } => all::handle(release, time),
-            AppArguments::Download { day } => download::handle(day),
-            AppArguments::Read { day } => read::handle(day),
-            AppArguments::Scaffold { day } => scaffold::handle(day),
-            AppArguments::Solve {
-                day,
+            AppArguments::All {
+                year,
+                limits,
+                jobs,
+                filter,
+                synthetic,
                 release,
                 time,
-                submit,
-            } => solve::handle(day, release, time, submit),
+            } => all::handle(year, release, time, jobs, &limits, &filter, synthetic),
+            AppArguments::Download { puzzle } => download::handle(puzzle),
+            AppArguments::Read { puzzle } => read::handle(puzzle),
+            AppArguments::Scaffold {
+                puzzle,
+                template,
+                answer_type,
+                force,
+            } => scaffold::handle(puzzle, template.as_deref(), answer_type.as_deref(), force),
+            AppArguments::Solve { puzzle, options } => solve::handle(puzzle, &options),
         },
     };
 }
@@ -0,0 +1,164 @@
+/// Caches for recursive dynamic programming. A cache is passed explicitly through the recursion,
+/// so nothing is shared between inputs, parts or tests unless the caller shares it.
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// A cache of computed values, implemented by [`Memo`] and [`DenseMemo`].
+pub trait Cache<K, V: Clone> {
+    fn get(&self, key: &K) -> Option<&V>;
+    fn insert(&mut self, key: K, value: V);
+    /// Forgets all values, e.g. before the next input.
+    fn clear(&mut self);
+
+    /// Returns the cached value of `key`, or computes and caches it.
+    /// `compute` gets the cache back, so it can recurse through it:
+    ///
+    /// ```ignore
+    /// fn fib(n: u64, memo: &mut Memo<u64, u64>) -> u64 {
+    ///     memo.get_or_compute(n, |memo, &n| match n {
+    ///         0 | 1 => n,
+    ///         _ => fib(n - 1, memo) + fib(n - 2, memo),
+    ///     })
+    /// }
+    /// ```
+    fn get_or_compute(&mut self, key: K, compute: impl FnOnce(&mut Self, &K) -> V) -> V
+    where
+        Self: Sized,
+    {
+        if let Some(value) = self.get(&key) {
+            return value.clone();
+        }
+        let value = compute(self, &key);
+        self.insert(key, value.clone());
+        value
+    }
+}
+
+/// A cache for arbitrary keys, backed by a [`HashMap`].
+#[derive(Debug, Clone)]
+pub struct Memo<K, V> {
+    values: HashMap<K, V>,
+}
+
+impl<K, V> Default for Memo<K, V> {
+    fn default() -> Self {
+        Self {
+            values: HashMap::new(),
+        }
+    }
+}
+
+impl<K, V> Memo<K, V> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+}
+
+impl<K: Hash + Eq, V: Clone> Cache<K, V> for Memo<K, V> {
+    fn get(&self, key: &K) -> Option<&V> {
+        self.values.get(key)
+    }
+
+    fn insert(&mut self, key: K, value: V) {
+        self.values.insert(key, value);
+    }
+
+    fn clear(&mut self) {
+        self.values.clear();
+    }
+}
+
+/// A cache for keys of `N` small integers, e.g. positions in two slices, backed by a flat array.
+/// Every key component has to be smaller than the corresponding dimension.
+#[derive(Debug, Clone)]
+pub struct DenseMemo<V, const N: usize> {
+    dims: [usize; N],
+    values: Vec<Option<V>>,
+}
+
+impl<V, const N: usize> DenseMemo<V, N> {
+    pub fn new(dims: [usize; N]) -> Self {
+        let size = dims.iter().product();
+        Self {
+            dims,
+            values: std::iter::repeat_with(|| None).take(size).collect(),
+        }
+    }
+
+    pub fn dims(&self) -> [usize; N] {
+        self.dims
+    }
+
+    fn index(&self, key: &[usize; N]) -> usize {
+        key.iter().zip(self.dims).fold(0, |index, (&k, dim)| {
+            debug_assert!(k < dim, "key {key:?} is out of bounds {:?}", self.dims);
+            index * dim + k
+        })
+    }
+}
+
+impl<V: Clone, const N: usize> Cache<[usize; N], V> for DenseMemo<V, N> {
+    fn get(&self, key: &[usize; N]) -> Option<&V> {
+        self.values[self.index(key)].as_ref()
+    }
+
+    fn insert(&mut self, key: [usize; N], value: V) {
+        let index = self.index(&key);
+        self.values[index] = Some(value);
+    }
+
+    fn clear(&mut self) {
+        self.values.fill(None);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fib(n: u64, memo: &mut impl Cache<u64, u64>) -> u64 {
+        memo.get_or_compute(n, |memo, &n| match n {
+            0 | 1 => n,
+            _ => fib(n - 1, memo) + fib(n - 2, memo),
+        })
+    }
+
+    /// The number of monotone lattice paths from `(0, 0)` to `(x, y)`.
+    fn paths(x: usize, y: usize, memo: &mut DenseMemo<u64, 2>) -> u64 {
+        memo.get_or_compute([x, y], |memo, &[x, y]| {
+            if x == 0 || y == 0 {
+                1
+            } else {
+                paths(x - 1, y, memo) + paths(x, y - 1, memo)
+            }
+        })
+    }
+
+    #[test]
+    fn test_memo() {
+        let mut memo = Memo::new();
+        assert_eq!(fib(90, &mut memo), 2_880_067_194_370_816_120);
+        assert_eq!(memo.len(), 91);
+        memo.clear();
+        assert!(memo.is_empty());
+    }
+
+    #[test]
+    fn test_dense_memo() {
+        let mut memo = DenseMemo::new([17, 17]);
+        assert_eq!(paths(16, 16, &mut memo), 601_080_390);
+        assert_eq!(memo.get(&[2, 2]), Some(&6));
+        assert_eq!(memo.get(&[16, 0]), Some(&1));
+        memo.clear();
+        assert_eq!(memo.get(&[2, 2]), None);
+        assert_eq!(paths(2, 3, &mut memo), 10);
+    }
+}
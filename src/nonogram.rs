@@ -0,0 +1,280 @@
+/// Nonograms: lines of cells that have to match a clue of run lengths, e.g. `#.###..#` for `1,3,1`.
+///
+/// Lines are given as bytes, [`FILLED`] and [`EMPTY`] are known cells and [`UNKNOWN`] can be either.
+/// All line queries are a bottom-up dynamic program over the positions in the line and the clue,
+/// its table is a [`DenseMemo`] that is filled from the end of the line.
+use crate::{Cache, DenseMemo, Grid};
+
+pub const FILLED: u8 = b'#';
+pub const EMPTY: u8 = b'.';
+pub const UNKNOWN: u8 = b'?';
+
+/// The table of the dynamic program of one line.
+struct Table<'a> {
+    line: &'a [u8],
+    clue: &'a [usize],
+    /// The number of cells from every position on that are not [`EMPTY`].
+    open: Vec<usize>,
+    /// The number of ways to complete `line[i..]` with `clue[j..]`, for every state `[i, j]`.
+    ways: DenseMemo<u64, 2>,
+}
+
+impl<'a> Table<'a> {
+    fn new(line: &'a [u8], clue: &'a [usize]) -> Self {
+        let (n, k) = (line.len(), clue.len());
+        let mut open = vec![0; n + 1];
+        for i in (0..n).rev() {
+            open[i] = if line[i] == EMPTY { 0 } else { open[i + 1] + 1 };
+        }
+
+        let mut table = Self {
+            line,
+            clue,
+            open,
+            ways: DenseMemo::new([n + 1, k + 1]),
+        };
+        for i in (0..=n).rev() {
+            for j in 0..=k {
+                let ways = if i == n {
+                    u64::from(j == k)
+                } else {
+                    let mut ways = 0;
+                    if line[i] != FILLED {
+                        ways += table.get(i + 1, j);
+                    }
+                    if let Some(next) = table.block(i, j) {
+                        ways += table.get(next, j + 1);
+                    }
+                    ways
+                };
+                table.ways.insert([i, j], ways);
+            }
+        }
+        table
+    }
+
+    /// Every state is filled in [`Table::new`], later states before earlier ones.
+    fn get(&self, i: usize, j: usize) -> u64 {
+        self.ways.get(&[i, j]).copied().unwrap_or(0)
+    }
+
+    /// If the run `clue[j]` can start at `i`, the position after it and its separator.
+    fn block(&self, i: usize, j: usize) -> Option<usize> {
+        let len = *self.clue.get(j)?;
+        let end = i + len;
+        if self.open[i] < len || self.line.get(end) == Some(&FILLED) {
+            return None;
+        }
+        Some((end + 1).min(self.line.len()))
+    }
+
+    fn enumerate(&self, i: usize, j: usize, current: &mut Vec<u8>, all: &mut Vec<Vec<u8>>) {
+        if self.get(i, j) == 0 {
+            return;
+        }
+        if i == self.line.len() {
+            all.push(current.clone());
+            return;
+        }
+        if self.line[i] != FILLED {
+            current.push(EMPTY);
+            self.enumerate(i + 1, j, current, all);
+            current.pop();
+        }
+        if let Some(next) = self.block(i, j) {
+            let len = current.len();
+            current.extend(std::iter::repeat_n(FILLED, self.clue[j]));
+            if next > i + self.clue[j] {
+                current.push(EMPTY);
+            }
+            self.enumerate(next, j + 1, current, all);
+            current.truncate(len);
+        }
+    }
+}
+
+/// The number of ways to complete `line` so that its runs of [`FILLED`] cells match `clue`.
+pub fn count(line: &[u8], clue: &[usize]) -> u64 {
+    Table::new(line, clue).get(0, 0)
+}
+
+/// All ways to complete `line` so that its runs of [`FILLED`] cells match `clue`,
+/// in lexicographic order with [`EMPTY`] before [`FILLED`].
+pub fn arrangements(line: &[u8], clue: &[usize]) -> Vec<Vec<u8>> {
+    let mut all = vec![];
+    Table::new(line, clue).enumerate(0, 0, &mut Vec::with_capacity(line.len()), &mut all);
+    all
+}
+
+/// The intersection of all arrangements: every cell that is the same in all of them is known,
+/// the others stay [`UNKNOWN`]. Returns [`None`] if there is no arrangement.
+pub fn solve_line(line: &[u8], clue: &[usize]) -> Option<Vec<u8>> {
+    let table = Table::new(line, clue);
+    if table.get(0, 0) == 0 {
+        return None;
+    }
+    let (n, k) = (line.len(), clue.len());
+
+    // which states can be reached from the start and still be completed.
+    let mut reachable = vec![false; (n + 1) * (k + 1)];
+    reachable[0] = true;
+    let mut can_be_empty = vec![false; n];
+    // a difference array of the cells that can be filled.
+    let mut filled = vec![0i32; n + 1];
+    for i in 0..n {
+        for j in 0..=k {
+            if !reachable[i * (k + 1) + j] {
+                continue;
+            }
+            if line[i] != FILLED && table.get(i + 1, j) > 0 {
+                can_be_empty[i] = true;
+                reachable[(i + 1) * (k + 1) + j] = true;
+            }
+            if let Some(next) = table.block(i, j).filter(|&next| table.get(next, j + 1) > 0) {
+                filled[i] += 1;
+                filled[i + clue[j]] -= 1;
+                if next > i + clue[j] {
+                    can_be_empty[i + clue[j]] = true;
+                }
+                reachable[next * (k + 1) + j + 1] = true;
+            }
+        }
+    }
+
+    let mut can_be_filled = 0;
+    Some(
+        (0..n)
+            .map(|i| {
+                can_be_filled += filled[i];
+                match (can_be_filled > 0, can_be_empty[i]) {
+                    (true, false) => FILLED,
+                    (false, true) => EMPTY,
+                    _ => UNKNOWN,
+                }
+            })
+            .collect(),
+    )
+}
+
+/// A picture puzzle with a clue for every row and every column.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Nonogram {
+    pub rows: Vec<Vec<usize>>,
+    pub cols: Vec<Vec<usize>>,
+}
+
+impl Nonogram {
+    /// Finds a picture that matches all clues. Lines are solved with [`solve_line`] until nothing
+    /// changes, if cells are still unknown then, one of them is guessed.
+    pub fn solve(&self) -> Option<Grid<u8>> {
+        let cells = std::iter::repeat_n(UNKNOWN, self.rows.len() * self.cols.len());
+        self.search(Grid::from_iter(cells, self.cols.len()))
+    }
+
+    fn search(&self, mut grid: Grid<u8>) -> Option<Grid<u8>> {
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for (y, clue) in self.rows.iter().enumerate() {
+                let row = grid.get_row(y);
+                let solved = solve_line(&row, clue)?;
+                if solved != row {
+                    grid.set_row(y, &solved);
+                    changed = true;
+                }
+            }
+            for (x, clue) in self.cols.iter().enumerate() {
+                let col = grid.get_col(x);
+                let solved = solve_line(&col, clue)?;
+                if solved != col {
+                    grid.set_col(x, &solved);
+                    changed = true;
+                }
+            }
+        }
+
+        let Some(guess) = grid.iter().position(|&c| c == UNKNOWN) else {
+            return Some(grid);
+        };
+        [FILLED, EMPTY].into_iter().find_map(|cell| {
+            let mut grid = grid.clone();
+            grid[guess] = cell;
+            self.search(grid)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_count() {
+        // the rows of the example of 2023 day 12.
+        assert_eq!(count(b"???.###", &[1, 1, 3]), 1);
+        assert_eq!(count(b".??..??...?##.", &[1, 1, 3]), 4);
+        assert_eq!(count(b"?#?#?#?#?#?#?#?", &[1, 3, 1, 6]), 1);
+        assert_eq!(count(b"????.#...#...", &[4, 1, 1]), 1);
+        assert_eq!(count(b"????.######..#####.", &[1, 6, 5]), 4);
+        assert_eq!(count(b"?###????????", &[3, 2, 1]), 10);
+
+        assert_eq!(count(b"", &[]), 1);
+        assert_eq!(count(b"??", &[]), 1);
+        assert_eq!(count(b"#", &[]), 0);
+        assert_eq!(count(b"???", &[1, 1]), 1);
+        assert_eq!(count(b"?????", &[1]), 5);
+    }
+
+    #[test]
+    fn test_long_line() {
+        assert_eq!(count(&vec![UNKNOWN; 100_000], &[1]), 100_000);
+    }
+
+    #[test]
+    fn test_arrangements() {
+        assert_eq!(
+            arrangements(b"?###????????", &[3, 2, 1]).len(),
+            count(b"?###????????", &[3, 2, 1]) as usize
+        );
+        let all: Vec<String> = arrangements(b"??#?", &[2])
+            .into_iter()
+            .map(|a| String::from_utf8(a).unwrap())
+            .collect();
+        assert_eq!(all, ["..##", ".##."]);
+    }
+
+    #[test]
+    fn test_solve_line() {
+        assert_eq!(solve_line(b"??????????", &[8]).unwrap(), b"??######??");
+        assert_eq!(solve_line(b"???", &[1, 1]).unwrap(), b"#.#");
+        assert_eq!(solve_line(b"??#?", &[2]).unwrap(), b".?#?");
+        assert_eq!(solve_line(b"?????", &[]).unwrap(), b".....");
+        assert_eq!(solve_line(b"#.#", &[3]), None);
+    }
+
+    #[test]
+    fn test_nonogram() {
+        // .#.
+        // ###
+        // .#.
+        let plus = Nonogram {
+            rows: vec![vec![1], vec![3], vec![1]],
+            cols: vec![vec![1], vec![3], vec![1]],
+        };
+        assert_eq!(&*plus.solve().unwrap(), b".#.###.#.");
+
+        // two solutions, the diagonals, need a guess.
+        let diagonal = Nonogram {
+            rows: vec![vec![1], vec![1]],
+            cols: vec![vec![1], vec![1]],
+        };
+        let picture = diagonal.solve().unwrap();
+        assert_eq!(&*picture, b"#..#");
+
+        let impossible = Nonogram {
+            rows: vec![vec![2], vec![]],
+            cols: vec![vec![], vec![1]],
+        };
+        assert_eq!(impossible.solve(), None);
+    }
+}
@@ -0,0 +1,303 @@
+/// Maps of `u64` that shift every range of a partition of the integers by its own offset,
+/// e.g. the almanac maps of 2023 day 5. Values outside all given ranges map to themselves.
+use std::ops::Range;
+
+use crate::IntervalSet;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PiecewiseMap {
+    /// The start of every piece and its offset, sorted by start. The first piece starts at 0,
+    /// every piece ends where the next one starts and the last one at [`u64::MAX`].
+    /// Neighbouring pieces have different offsets.
+    pieces: Vec<(u64, i64)>,
+}
+
+impl Default for PiecewiseMap {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+impl PiecewiseMap {
+    /// The map of every value to itself.
+    pub fn identity() -> Self {
+        Self {
+            pieces: vec![(0, 0)],
+        }
+    }
+
+    /// The pieces as their ranges and offsets, in order.
+    pub fn pieces(&self) -> impl Iterator<Item = (Range<u64>, i64)> + '_ {
+        let ends = self.pieces.iter().skip(1).map(|&(start, _)| start);
+        self.pieces
+            .iter()
+            .zip(ends.chain([u64::MAX]))
+            .map(|(&(start, offset), end)| (start..end, offset))
+    }
+
+    fn piece(&self, x: u64) -> usize {
+        self.pieces.partition_point(|&(start, _)| start <= x) - 1
+    }
+
+    /// Maps `source` to the range starting at `destination`, overriding what it mapped to before.
+    ///
+    /// # Panics
+    /// If the destination range does not fit into `u64` or the offset does not fit into `i64`.
+    pub fn insert(&mut self, source: Range<u64>, destination: u64) {
+        if source.is_empty() {
+            return;
+        }
+        destination
+            .checked_add(source.end - source.start)
+            .expect("destination range overflows");
+        let offset = i64::try_from(i128::from(destination) - i128::from(source.start))
+            .expect("offset does not fit into i64");
+
+        let first = self
+            .pieces
+            .partition_point(|&(start, _)| start < source.start);
+        let last = self
+            .pieces
+            .partition_point(|&(start, _)| start <= source.end);
+        // the piece that contains `source.end` continues after it.
+        let after = self.pieces[self.piece(source.end)].1;
+        let mut new = vec![(source.start, offset)];
+        if source.end < u64::MAX {
+            new.push((source.end, after));
+        }
+        self.pieces.splice(first..last, new);
+        self.normalize();
+    }
+
+    /// Merges neighbouring pieces with the same offset.
+    fn normalize(&mut self) {
+        self.pieces.dedup_by(|next, prev| next.1 == prev.1);
+    }
+
+    pub fn get(&self, x: u64) -> u64 {
+        shift(x, self.pieces[self.piece(x)].1)
+    }
+
+    /// The map that applies `self` first and then `other`.
+    ///
+    /// # Panics
+    /// If the offset of a composed piece does not fit into `i64`.
+    pub fn then(&self, other: &Self) -> Self {
+        let mut pieces = vec![];
+        for (range, offset) in self.pieces() {
+            let image = shift_range(range, offset);
+            for (next, next_offset) in other.pieces() {
+                let start = image.start.max(next.start);
+                let end = image.end.min(next.end);
+                if start < end {
+                    let offset_sum = offset
+                        .checked_add(next_offset)
+                        .expect("offset does not fit into i64");
+                    pieces.push((shift(start, -i128::from(offset)), offset_sum));
+                }
+            }
+        }
+        let mut map = Self { pieces };
+        map.normalize();
+        map
+    }
+
+    /// The map that undoes `self`, if every value is the image of exactly one value.
+    ///
+    /// # Panics
+    /// If an offset is [`i64::MIN`], the offset of the inverse does not fit into `i64`.
+    pub fn inverse(&self) -> Option<Self> {
+        let mut images: Vec<(Range<u64>, i64)> = self
+            .pieces()
+            .map(|(range, offset)| (shift_range(range, offset), offset))
+            .collect();
+        images.sort_unstable_by_key(|(image, _)| image.start);
+        // the images have to tile the integers without gaps and overlaps.
+        let ends = images.iter().map(|(image, _)| image.end);
+        let starts = images.iter().skip(1).map(|(image, _)| image.start);
+        if images[0].0.start != 0 || !ends.eq(starts.chain([u64::MAX])) {
+            return None;
+        }
+        let mut map = Self {
+            pieces: images
+                .into_iter()
+                .map(|(image, offset)| {
+                    let inverse = offset.checked_neg().expect("offset does not fit into i64");
+                    (image.start, inverse)
+                })
+                .collect(),
+        };
+        map.normalize();
+        Some(map)
+    }
+
+    /// The images of all values in `set`.
+    pub fn image(&self, set: &IntervalSet) -> IntervalSet {
+        set.ranges()
+            .iter()
+            .flat_map(|range| self.split(range.clone()))
+            .map(|(part, offset)| shift_range(part, offset))
+            .collect()
+    }
+
+    /// The smallest image of a value in `range`, [`None`] if it is empty.
+    pub fn min(&self, range: Range<u64>) -> Option<u64> {
+        // every piece is increasing, so the minimum is at the start of one of them.
+        self.split(range)
+            .map(|(part, offset)| shift(part.start, offset))
+            .min()
+    }
+
+    /// Splits `range` at the starts of the pieces, with the offset of each part.
+    fn split(&self, range: Range<u64>) -> impl Iterator<Item = (Range<u64>, i64)> + '_ {
+        let first = if range.is_empty() {
+            self.pieces.len()
+        } else {
+            self.piece(range.start)
+        };
+        self.pieces().skip(first).map_while(move |(piece, offset)| {
+            let part = piece.start.max(range.start)..piece.end.min(range.end);
+            (!part.is_empty()).then_some((part, offset))
+        })
+    }
+}
+
+/// Moves `x` by `offset`. Every piece maps into `u64`, so this only fails on a broken invariant.
+fn shift(x: u64, offset: impl Into<i128>) -> u64 {
+    u64::try_from(i128::from(x) + offset.into()).expect("value is shifted out of u64")
+}
+
+/// Moves a range by `offset`. Its end may be [`u64::MAX`] itself, the end of the last piece.
+fn shift_range(range: Range<u64>, offset: i64) -> Range<u64> {
+    shift(range.start, offset)..shift(range.end, offset)
+}
+
+impl FromIterator<(Range<u64>, u64)> for PiecewiseMap {
+    /// Maps every source range to the range starting at its destination,
+    /// later ranges override earlier ones where they overlap.
+    fn from_iter<I: IntoIterator<Item = (Range<u64>, u64)>>(iter: I) -> Self {
+        let mut map = Self::identity();
+        iter.into_iter()
+            .for_each(|(source, destination)| map.insert(source, destination));
+        map
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The seed-to-soil and soil-to-fertilizer maps of the example of 2023 day 5.
+    fn soil() -> PiecewiseMap {
+        [(98..100, 50), (50..98, 52)].into_iter().collect()
+    }
+    fn fertilizer() -> PiecewiseMap {
+        [(15..52, 0), (52..54, 37), (0..15, 39)]
+            .into_iter()
+            .collect()
+    }
+
+    #[test]
+    fn test_get() {
+        let soil = soil();
+        assert_eq!([79, 14, 55, 13].map(|x| soil.get(x)), [81, 14, 57, 13]);
+        assert_eq!(soil.get(98), 50);
+        assert_eq!(soil.get(100), 100);
+        assert_eq!(soil.pieces().count(), 4);
+        assert_eq!(PiecewiseMap::default().get(u64::MAX - 1), u64::MAX - 1);
+
+        let mut map = soil.clone();
+        map.insert(0..200, 0);
+        assert_eq!(map, PiecewiseMap::identity());
+    }
+
+    #[test]
+    fn test_then() {
+        let (soil, fertilizer) = (soil(), fertilizer());
+        let both = soil.then(&fertilizer);
+        for x in 0..200 {
+            assert_eq!(both.get(x), fertilizer.get(soil.get(x)));
+        }
+        assert_eq!(PiecewiseMap::identity().then(&soil), soil);
+    }
+
+    #[test]
+    fn test_inverse() {
+        let (soil, fertilizer) = (soil(), fertilizer());
+        let both = soil.then(&fertilizer);
+        let inverse = both.inverse().unwrap();
+        for x in 0..200 {
+            assert_eq!(inverse.get(both.get(x)), x);
+        }
+        assert_eq!(both.then(&inverse), PiecewiseMap::identity());
+
+        // 0 and 10 both map to 10.
+        let collision: PiecewiseMap = [(0..1, 10)].into_iter().collect();
+        assert_eq!(collision.inverse(), None);
+    }
+
+    #[test]
+    fn test_image() {
+        let soil = soil();
+        let seeds: IntervalSet = [79..93, 55..68].into_iter().collect();
+        assert_eq!(soil.image(&seeds), [57..70, 81..95].into_iter().collect());
+        let edge = IntervalSet::from(96..102);
+        assert_eq!(
+            soil.image(&edge),
+            [50..52, 98..100, 100..102].into_iter().collect()
+        );
+
+        assert_eq!(soil.min(79..93), Some(81));
+        assert_eq!(soil.min(90..101), Some(50));
+        assert_eq!(soil.min(5..5), None);
+    }
+
+    #[test]
+    fn test_bounds() {
+        let top = u64::MAX - 10;
+        let map: PiecewiseMap = [(top - 10..top, top), (top..u64::MAX, top - 10)]
+            .into_iter()
+            .collect();
+        assert_eq!(map.get(top - 1), u64::MAX - 1);
+        assert_eq!(map.get(u64::MAX - 1), top - 1);
+        assert_eq!(map.min(top - 10..u64::MAX), Some(top - 10));
+        let inverse = map.inverse().unwrap();
+        assert_eq!(map.then(&inverse), PiecewiseMap::identity());
+
+        // the upper half moves to the bottom, the lower half to the top.
+        let half = 1 << 63;
+        let swap: PiecewiseMap = [(0..half, half - 1), (half..u64::MAX, 0)]
+            .into_iter()
+            .collect();
+        assert_eq!(swap.get(half), 0);
+        assert_eq!(swap.get(half - 1), u64::MAX - 1);
+        let collision: PiecewiseMap = [(half..u64::MAX, 0)].into_iter().collect();
+        assert_eq!(collision.inverse(), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "offset does not fit into i64")]
+    fn test_offset_overflow() {
+        let map: PiecewiseMap = [(u64::MAX - 10..u64::MAX, 0)].into_iter().collect();
+        map.get(0);
+    }
+
+    #[test]
+    #[should_panic(expected = "offset does not fit into i64")]
+    fn test_inverse_offset_overflow() {
+        let half = 1 << 63;
+        let swap: PiecewiseMap = [(0..half, half - 1), (half..u64::MAX, 0)]
+            .into_iter()
+            .collect();
+        swap.inverse();
+    }
+
+    #[test]
+    #[should_panic(expected = "offset does not fit into i64")]
+    fn test_composed_offset_overflow() {
+        let quarter = 1 << 62;
+        let first: PiecewiseMap = [(0..10, quarter)].into_iter().collect();
+        let second: PiecewiseMap = [(quarter..quarter + 10, 2 * quarter)].into_iter().collect();
+        first.then(&second);
+    }
+}
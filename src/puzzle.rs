@@ -0,0 +1,75 @@
+use std::fmt::Display;
+
+use crate::template::config;
+use crate::{AllDays, Day, Year};
+
+/// Identifies a puzzle by its year and day.
+///
+/// A puzzle without a year belongs to the default year of `aoc.toml` and uses the flat layout
+/// (`src/bin/NN.rs`, `data/inputs/NN.txt`). A puzzle with a year uses the per-year layout
+/// (`src/bin/YYYY_NN.rs`, `data/YYYY/inputs/NN.txt`).
+///
+/// ```
+/// # use advent_of_code::{day, year, Puzzle};
+/// let puzzle = Puzzle::new(year!(2022), day!(8));
+/// assert_eq!(puzzle.bin_name(), "2022_08");
+/// assert_eq!(Puzzle::from(day!(8)).bin_name(), "08");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Puzzle {
+    pub year: Option<Year>,
+    pub day: Day,
+}
+
+impl Puzzle {
+    pub const fn new(year: Year, day: Day) -> Self {
+        Self {
+            year: Some(year),
+            day,
+        }
+    }
+
+    /// The name of the solution binary, e.g. `08` or `2022_08`.
+    pub fn bin_name(&self) -> String {
+        match self.year {
+            Some(year) => format!("{year}_{}", self.day),
+            None => self.day.to_string(),
+        }
+    }
+
+    /// The year of this puzzle, falling back to the configured default year.
+    pub fn resolve_year(&self) -> Option<Year> {
+        self.year.or_else(|| config::get().year())
+    }
+}
+
+impl From<Day> for Puzzle {
+    fn from(day: Day) -> Self {
+        Self { year: None, day }
+    }
+}
+
+impl Display for Puzzle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.year {
+            Some(year) => write!(f, "{year} Day {}", self.day),
+            None => write!(f, "Day {}", self.day),
+        }
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// An iterator that yields every day of the configured year(s), or of `year` if given.
+pub fn all_days(year: Option<Year>) -> impl Iterator<Item = Puzzle> {
+    let config = config::get();
+    let years = match year {
+        Some(year) => vec![Some(year)],
+        None if config.years.is_empty() => vec![config.year()],
+        None => config.years.iter().copied().map(Some).collect(),
+    };
+
+    years
+        .into_iter()
+        .flat_map(move |year| AllDays::new().map(move |day| config.puzzle(year, day)))
+}
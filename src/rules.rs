@@ -0,0 +1,478 @@
+/// Workflows of rules that send records with named integer fields to other workflows,
+/// until they are accepted or rejected, e.g. 2023 day 19.
+///
+/// A workflow is written as `name{a<2006:qkq,m>2090:A,rfg}`: every rule but the last compares
+/// one field with a number and names a target, the last rule always applies.
+/// `A` and `R` accept and reject.
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::error::Error;
+use std::fmt::Display;
+use std::ops::Range;
+use std::str::FromStr;
+
+use crate::IntervalSet;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Target {
+    Accept,
+    Reject,
+    Workflow(String),
+}
+
+impl From<&str> for Target {
+    fn from(s: &str) -> Self {
+        match s {
+            "A" => Self::Accept,
+            "R" => Self::Reject,
+            name => Self::Workflow(name.to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Condition {
+    Less(String, u64),
+    Greater(String, u64),
+}
+
+impl Condition {
+    pub fn field(&self) -> &str {
+        match self {
+            Condition::Less(field, _) | Condition::Greater(field, _) => field,
+        }
+    }
+
+    /// The values of the field that satisfy the condition. The range is half-open,
+    /// so [`u64::MAX`] itself never satisfies a [`Condition::Greater`].
+    pub fn range(&self) -> Range<u64> {
+        match self {
+            Condition::Less(_, n) => 0..*n,
+            Condition::Greater(_, n) => n.saturating_add(1)..u64::MAX,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Rule {
+    /// [`None`] if the rule always applies.
+    pub condition: Option<Condition>,
+    pub target: Target,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Workflow {
+    pub name: String,
+    pub rules: Vec<Rule>,
+}
+
+impl FromStr for Workflow {
+    type Err = RuleFromStrError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || RuleFromStrError(s.to_string());
+        let (name, rules) = s
+            .trim()
+            .strip_suffix('}')
+            .and_then(|s| s.split_once('{'))
+            .ok_or_else(error)?;
+
+        let rules = rules
+            .split(',')
+            .map(|rule| {
+                let Some((condition, target)) = rule.split_once(':') else {
+                    return Ok(Rule {
+                        condition: None,
+                        target: rule.into(),
+                    });
+                };
+                let (field, n, less) = if let Some((field, n)) = condition.split_once('<') {
+                    (field, n, true)
+                } else {
+                    let (field, n) = condition.split_once('>').ok_or_else(error)?;
+                    (field, n, false)
+                };
+                let n = n.parse().map_err(|_| error())?;
+                let field = field.to_string();
+                Ok(Rule {
+                    condition: Some(if less {
+                        Condition::Less(field, n)
+                    } else {
+                        Condition::Greater(field, n)
+                    }),
+                    target: target.into(),
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if name.is_empty() || rules.last().is_none_or(|r| r.condition.is_some()) {
+            return Err(error());
+        }
+        Ok(Self {
+            name: name.to_string(),
+            rules,
+        })
+    }
+}
+
+/// A record with named fields, e.g. `{x=787,m=2655,a=1222,s=2876}`.
+pub type Record = HashMap<String, u64>;
+
+pub fn parse_record(s: &str) -> Result<Record, RuleFromStrError> {
+    let error = || RuleFromStrError(s.to_string());
+    let fields = s
+        .trim()
+        .strip_prefix('{')
+        .and_then(|s| s.strip_suffix('}'))
+        .ok_or_else(error)?;
+    fields
+        .split(',')
+        .map(|field| {
+            let (name, value) = field.split_once('=').ok_or_else(error)?;
+            Ok((name.to_string(), value.parse().map_err(|_| error())?))
+        })
+        .collect()
+}
+
+/// The possible values of every field, records with all fields in their sets are inside.
+/// Every field that a condition tests has to be in the region.
+pub type Region = BTreeMap<String, IntervalSet>;
+
+/// The number of records in a region, [`None`] if it does not fit into `u64`.
+pub fn volume(region: &Region) -> Option<u64> {
+    region
+        .values()
+        .try_fold(1u64, |volume, set| volume.checked_mul(set.len()))
+}
+
+/// A set of workflows, one per line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuleSet {
+    workflows: HashMap<String, Workflow>,
+}
+
+impl FromStr for RuleSet {
+    type Err = RuleFromStrError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let workflows = s
+            .lines()
+            .filter(|l| !l.trim().is_empty())
+            .map(|l| Workflow::from_str(l).map(|w| (w.name.clone(), w)))
+            .collect::<Result<_, _>>()?;
+        Ok(Self { workflows })
+    }
+}
+
+impl RuleSet {
+    pub fn workflow(&self, name: &str) -> Option<&Workflow> {
+        self.workflows.get(name)
+    }
+
+    fn get(&self, name: &str) -> Result<&Workflow, EvaluationError> {
+        self.workflow(name)
+            .ok_or_else(|| EvaluationError::UnknownWorkflow(name.to_string()))
+    }
+
+    /// Whether `record` is accepted when it starts at the workflow `start`.
+    pub fn accepts(&self, start: &str, record: &Record) -> Result<bool, EvaluationError> {
+        let mut name = start;
+        let mut visited = HashSet::new();
+        loop {
+            if !visited.insert(name) {
+                return Err(EvaluationError::Cycle(name.to_string()));
+            }
+            let rule = self
+                .get(name)?
+                .rules
+                .iter()
+                .find_map(|rule| {
+                    let Some(condition) = &rule.condition else {
+                        return Some(Ok(rule));
+                    };
+                    match record.get(condition.field()) {
+                        Some(value) => condition.range().contains(value).then_some(Ok(rule)),
+                        None => Some(Err(EvaluationError::UnknownField(
+                            condition.field().to_string(),
+                        ))),
+                    }
+                })
+                .expect("the last rule always applies")?;
+            match &rule.target {
+                Target::Accept => return Ok(true),
+                Target::Reject => return Ok(false),
+                Target::Workflow(next) => name = next,
+            }
+        }
+    }
+
+    /// Evaluates all records of `region` at once, starting at the workflow `start`.
+    /// Returns disjoint regions that together contain exactly the accepted records,
+    /// or an error if a condition tests a field that is not in `region`.
+    pub fn accepted(&self, start: &str, region: Region) -> Result<Vec<Region>, EvaluationError> {
+        let mut accepted = vec![];
+        self.propagate(start, region, &mut vec![], &mut accepted)?;
+        Ok(accepted)
+    }
+
+    fn propagate<'a>(
+        &'a self,
+        name: &'a str,
+        mut region: Region,
+        path: &mut Vec<&'a str>,
+        accepted: &mut Vec<Region>,
+    ) -> Result<(), EvaluationError> {
+        if path.contains(&name) {
+            return Err(EvaluationError::Cycle(name.to_string()));
+        }
+        path.push(name);
+        for rule in &self.get(name)?.rules {
+            let matching = match &rule.condition {
+                None => std::mem::take(&mut region),
+                Some(condition) => {
+                    let values = region.get(condition.field()).cloned().ok_or_else(|| {
+                        EvaluationError::UnknownField(condition.field().to_string())
+                    })?;
+                    let mut matching = region.clone();
+                    matching.insert(
+                        condition.field().to_string(),
+                        values.intersection(condition.range()),
+                    );
+                    region.insert(
+                        condition.field().to_string(),
+                        values.difference(condition.range()),
+                    );
+                    matching
+                }
+            };
+
+            if matching.values().all(|set| !set.is_empty()) {
+                match &rule.target {
+                    Target::Accept => accepted.push(matching),
+                    Target::Reject => {}
+                    Target::Workflow(next) => self.propagate(next, matching, path, accepted)?,
+                }
+            }
+            if region.is_empty() || region.values().any(IntervalSet::is_empty) {
+                break;
+            }
+        }
+        path.pop();
+        Ok(())
+    }
+
+    /// The workflows that no record can reach from `start`, in alphabetical order.
+    /// Only the targets of the rules are considered, not whether their conditions can hold.
+    pub fn unreachable(&self, start: &str) -> Vec<&str> {
+        let mut reached = HashSet::from([start]);
+        let mut stack = vec![start];
+        while let Some(name) = stack.pop() {
+            for rule in self.workflow(name).into_iter().flat_map(|w| &w.rules) {
+                if let Target::Workflow(next) = &rule.target {
+                    if reached.insert(next) {
+                        stack.push(next);
+                    }
+                }
+            }
+        }
+        let mut unreachable: Vec<&str> = self
+            .workflows
+            .keys()
+            .map(String::as_str)
+            .filter(|name| !reached.contains(name))
+            .collect();
+        unreachable.sort_unstable();
+        unreachable
+    }
+
+    /// Finds a cycle of workflows that send records to each other,
+    /// returned as the names of its workflows in order.
+    pub fn find_cycle(&self) -> Option<Vec<&str>> {
+        let mut names: Vec<&str> = self.workflows.keys().map(String::as_str).collect();
+        names.sort_unstable();
+        // the workflows that are finished, and those on the current path.
+        let mut done = HashSet::new();
+        for start in names {
+            let mut path = vec![];
+            if let Some(cycle) = self.visit(start, &mut path, &mut done) {
+                return Some(cycle);
+            }
+        }
+        None
+    }
+
+    fn visit<'a>(
+        &'a self,
+        name: &'a str,
+        path: &mut Vec<&'a str>,
+        done: &mut HashSet<&'a str>,
+    ) -> Option<Vec<&'a str>> {
+        if let Some(i) = path.iter().position(|&n| n == name) {
+            return Some(path[i..].to_vec());
+        }
+        if !done.insert(name) {
+            return None;
+        }
+        path.push(name);
+        for rule in self.workflow(name).into_iter().flat_map(|w| &w.rules) {
+            if let Target::Workflow(next) = &rule.target {
+                if let Some(cycle) = self.visit(next, path, done) {
+                    return Some(cycle);
+                }
+            }
+        }
+        path.pop();
+        None
+    }
+}
+
+/// A line of a workflow or a record that could not be parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuleFromStrError(String);
+
+impl Error for RuleFromStrError {}
+
+impl Display for RuleFromStrError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid workflow or record: {}", self.0)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EvaluationError {
+    UnknownWorkflow(String),
+    /// A condition tests a field that the record or region does not have.
+    UnknownField(String),
+    /// A workflow was reached again while evaluating.
+    Cycle(String),
+}
+
+impl Error for EvaluationError {}
+
+impl Display for EvaluationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EvaluationError::UnknownWorkflow(name) => write!(f, "unknown workflow: {name}"),
+            EvaluationError::UnknownField(name) => write!(f, "unknown field: {name}"),
+            EvaluationError::Cycle(name) => write!(f, "workflow {name} is part of a cycle"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The workflows of the example of 2023 day 19.
+    const EXAMPLE: &str = "\
+px{a<2006:qkq,m>2090:A,rfg}
+pv{a>1716:R,A}
+lnx{m>1548:A,A}
+rfg{s<537:gd,x>2440:R,A}
+qs{s>3448:A,lnx}
+qkq{x<1416:A,crn}
+crn{x>2662:A,R}
+in{s<1351:px,qqz}
+qqz{s>2770:qs,m<1801:hdj,R}
+gd{a>3333:R,R}
+hdj{m>838:A,pv}";
+
+    fn xmas() -> Region {
+        ["x", "m", "a", "s"]
+            .into_iter()
+            .map(|f| (f.to_string(), IntervalSet::from(1..4001)))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse() {
+        let workflow: Workflow = "px{a<2006:qkq,m>2090:A,rfg}".parse().unwrap();
+        assert_eq!(workflow.rules.len(), 3);
+        assert_eq!(
+            workflow.rules[1],
+            Rule {
+                condition: Some(Condition::Greater("m".to_string(), 2090)),
+                target: Target::Accept,
+            }
+        );
+        assert!("px{a<2006:qkq}".parse::<Workflow>().is_err());
+        assert!("px{a=2006:qkq,A}".parse::<Workflow>().is_err());
+        assert!("px".parse::<Workflow>().is_err());
+        assert!(parse_record("{x=1,m=}").is_err());
+    }
+
+    #[test]
+    fn test_accepts() {
+        let rules: RuleSet = EXAMPLE.parse().unwrap();
+        let record = parse_record("{x=787,m=2655,a=1222,s=2876}").unwrap();
+        assert_eq!(rules.accepts("in", &record), Ok(true));
+        let record = parse_record("{x=1679,m=44,a=2067,s=496}").unwrap();
+        assert_eq!(rules.accepts("in", &record), Ok(false));
+        assert_eq!(
+            rules.accepts("nope", &record),
+            Err(EvaluationError::UnknownWorkflow("nope".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_accepted() {
+        let rules: RuleSet = EXAMPLE.parse().unwrap();
+        let accepted = rules.accepted("in", xmas()).unwrap();
+        assert_eq!(
+            accepted.iter().map(volume).sum::<Option<u64>>(),
+            Some(167_409_079_868_000)
+        );
+
+        let region = Region::from([("x".to_string(), IntervalSet::from(1..3))]);
+        let rules: RuleSet = "in{x<2:A,R}".parse().unwrap();
+        let accepted = rules.accepted("in", region).unwrap();
+        assert_eq!(accepted.len(), 1);
+        assert!(accepted[0]["x"].contains(1) && !accepted[0]["x"].contains(2));
+
+        let region = Region::from([("x".to_string(), IntervalSet::from(1..3))]);
+        let rules: RuleSet = "in{m>5:A,R}".parse().unwrap();
+        assert_eq!(
+            rules.accepted("in", region),
+            Err(EvaluationError::UnknownField("m".to_string()))
+        );
+        let record = parse_record("{x=1}").unwrap();
+        assert_eq!(
+            rules.accepts("in", &record),
+            Err(EvaluationError::UnknownField("m".to_string()))
+        );
+
+        let region = Region::from([
+            ("x".to_string(), IntervalSet::from(0..u64::MAX)),
+            ("m".to_string(), IntervalSet::from(0..2)),
+        ]);
+        assert_eq!(volume(&region), None);
+        assert_eq!(volume(&Region::new()), Some(1));
+    }
+
+    #[test]
+    fn test_structure() {
+        let rules: RuleSet = EXAMPLE.parse().unwrap();
+        assert!(rules.unreachable("in").is_empty());
+        assert_eq!(
+            rules.unreachable("qs"),
+            ["crn", "gd", "hdj", "in", "pv", "px", "qkq", "qqz", "rfg"]
+        );
+        assert_eq!(rules.find_cycle(), None);
+
+        let rules: RuleSet = "in{x<2:a,R}\na{m>5:b,A}\nb{c}\nc{a}\nd{A}".parse().unwrap();
+        assert_eq!(rules.find_cycle(), Some(vec!["a", "b", "c"]));
+        assert_eq!(rules.unreachable("in"), ["d"]);
+        let record = parse_record("{x=1,m=6}").unwrap();
+        assert_eq!(
+            rules.accepts("in", &record),
+            Err(EvaluationError::Cycle("a".to_string()))
+        );
+        let region = Region::from([
+            ("x".to_string(), IntervalSet::from(1..3)),
+            ("m".to_string(), IntervalSet::from(1..10)),
+        ]);
+        assert_eq!(
+            rules.accepted("in", region),
+            Err(EvaluationError::Cycle("a".to_string()))
+        );
+    }
+}
@@ -0,0 +1,227 @@
+/// Axis-aligned boxes that fall under gravity and come to rest on each other,
+/// with the support relationships between them.
+use crate::{Dag, DominatorTree, Grid};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Axis {
+    X,
+    Y,
+    Z,
+}
+
+impl Axis {
+    fn index(self) -> usize {
+        match self {
+            Axis::X => 0,
+            Axis::Y => 1,
+            Axis::Z => 2,
+        }
+    }
+}
+
+/// A box of unit cubes, `min` and `max` are inclusive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Cuboid {
+    pub min: [u32; 3],
+    pub max: [u32; 3],
+}
+
+impl Cuboid {
+    /// The box between two opposite corners, in any order.
+    pub fn new(a: [u32; 3], b: [u32; 3]) -> Self {
+        Self {
+            min: [0, 1, 2].map(|i| a[i].min(b[i])),
+            max: [0, 1, 2].map(|i| a[i].max(b[i])),
+        }
+    }
+
+    /// The number of unit cubes along `axis`.
+    pub fn extent(&self, axis: Axis) -> u32 {
+        self.max[axis.index()] - self.min[axis.index()] + 1
+    }
+}
+
+/// Boxes after they have settled, see [`Stack::settle`].
+#[derive(Debug, Clone)]
+pub struct Stack {
+    boxes: Vec<Cuboid>,
+    supports: Vec<Vec<usize>>,
+    supported_by: Vec<Vec<usize>>,
+    /// The ground is an extra node after the boxes that dominates all of them.
+    dominators: DominatorTree,
+}
+
+impl Stack {
+    /// Lets every box fall towards lower coordinates along `axis` until it rests on `floor`,
+    /// the lowest coordinate a box can occupy, or on another box. Boxes do not rotate or tip over.
+    ///
+    /// The boxes keep their order, so indices into `boxes` can be used for the queries.
+    pub fn settle(mut boxes: Vec<Cuboid>, axis: Axis, floor: u32) -> Self {
+        let up = axis.index();
+        // the two axes of the plane below the boxes.
+        let (a, b) = ((up + 1) % 3, (up + 2) % 3);
+
+        let origin = (
+            boxes.iter().map(|c| c.min[a]).min().unwrap_or(0),
+            boxes.iter().map(|c| c.min[b]).min().unwrap_or(0),
+        );
+        let size = (
+            boxes
+                .iter()
+                .map(|c| c.max[a] - origin.0 + 1)
+                .max()
+                .unwrap_or(0) as usize,
+            boxes
+                .iter()
+                .map(|c| c.max[b] - origin.1 + 1)
+                .max()
+                .unwrap_or(0) as usize,
+        );
+        // the top of the highest box in every column, and which box that is.
+        let mut heights: Grid<Option<(u32, usize)>> =
+            Grid::from_iter(std::iter::repeat_n(None, size.0 * size.1), size.1.max(1));
+
+        let mut order: Vec<usize> = (0..boxes.len()).collect();
+        order.sort_unstable_by_key(|&i| boxes[i].min[up]);
+
+        let mut supports = vec![vec![]; boxes.len()];
+        let mut supported_by = vec![vec![]; boxes.len()];
+        for i in order {
+            let cuboid = &mut boxes[i];
+            let footprint: Vec<(usize, usize)> = (cuboid.min[a]..=cuboid.max[a])
+                .flat_map(|x| {
+                    (cuboid.min[b]..=cuboid.max[b])
+                        .map(move |y| ((x - origin.0) as usize, (y - origin.1) as usize))
+                })
+                .collect();
+
+            let rest = footprint
+                .iter()
+                .filter_map(|&cell| heights[cell])
+                .map(|(top, _)| top + 1)
+                .max()
+                .unwrap_or(floor);
+            for &cell in &footprint {
+                if let Some((top, below)) = heights[cell] {
+                    if top + 1 == rest && !supported_by[i].contains(&below) {
+                        supported_by[i].push(below);
+                        supports[below].push(i);
+                    }
+                }
+            }
+
+            let extent = cuboid.extent(axis);
+            cuboid.min[up] = rest;
+            cuboid.max[up] = rest + extent - 1;
+            for cell in footprint {
+                heights[cell] = Some((cuboid.max[up], i));
+            }
+        }
+
+        let ground = boxes.len();
+        let mut dag = Dag::new(boxes.len() + 1);
+        for (i, below) in supported_by.iter().enumerate() {
+            if below.is_empty() {
+                dag.add_edge(ground, i);
+            }
+            for &j in below {
+                dag.add_edge(j, i);
+            }
+        }
+        let dominators = dag.dominator_tree(ground);
+
+        Self {
+            boxes,
+            supports,
+            supported_by,
+            dominators,
+        }
+    }
+
+    /// The boxes at their resting positions.
+    pub fn boxes(&self) -> &[Cuboid] {
+        &self.boxes
+    }
+
+    /// The boxes that rest directly on box `i`.
+    pub fn supports(&self, i: usize) -> &[usize] {
+        &self.supports[i]
+    }
+
+    /// The boxes that box `i` rests on directly, empty if it lies on the floor.
+    pub fn supported_by(&self, i: usize) -> &[usize] {
+        &self.supported_by[i]
+    }
+
+    /// Whether box `i` can be removed without any other box falling.
+    pub fn is_safe_to_remove(&self, i: usize) -> bool {
+        self.supports[i]
+            .iter()
+            .all(|&above| self.supported_by[above].len() > 1)
+    }
+
+    /// The number of other boxes that fall if box `i` is removed,
+    /// including those that fall because falling boxes no longer support them.
+    pub fn chain_reaction(&self, i: usize) -> usize {
+        self.dominators.subtree_size(i) - 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The bricks of the example of 2023 day 22.
+    fn bricks() -> Vec<Cuboid> {
+        [
+            ([1, 0, 1], [1, 2, 1]),
+            ([0, 0, 2], [2, 0, 2]),
+            ([0, 2, 3], [2, 2, 3]),
+            ([0, 0, 4], [0, 2, 4]),
+            ([2, 0, 5], [2, 2, 5]),
+            ([0, 1, 6], [2, 1, 6]),
+            ([1, 1, 8], [1, 1, 9]),
+        ]
+        .into_iter()
+        .map(|(a, b)| Cuboid::new(a, b))
+        .collect()
+    }
+
+    #[test]
+    fn test_settle() {
+        let stack = Stack::settle(bricks(), Axis::Z, 1);
+        let bottoms: Vec<u32> = stack.boxes().iter().map(|c| c.min[2]).collect();
+        assert_eq!(bottoms, [1, 2, 2, 3, 3, 4, 5]);
+        assert_eq!(stack.boxes()[6].max[2], 6);
+        assert_eq!(stack.supports(0), [1, 2]);
+        assert_eq!(stack.supported_by(3), [1, 2]);
+        assert!(stack.supported_by(0).is_empty());
+    }
+
+    #[test]
+    fn test_queries() {
+        let stack = Stack::settle(bricks(), Axis::Z, 1);
+        let safe: Vec<bool> = (0..7).map(|i| stack.is_safe_to_remove(i)).collect();
+        assert_eq!(safe, [false, true, true, true, true, false, true]);
+        let falling: Vec<usize> = (0..7).map(|i| stack.chain_reaction(i)).collect();
+        assert_eq!(falling, [6, 0, 0, 0, 0, 1, 0]);
+    }
+
+    #[test]
+    fn test_other_axis() {
+        // the same bricks falling towards lower x after swapping x and z.
+        let swapped = bricks()
+            .into_iter()
+            .map(|c| {
+                Cuboid::new(
+                    [c.min[2], c.min[1], c.min[0]],
+                    [c.max[2], c.max[1], c.max[0]],
+                )
+            })
+            .collect();
+        let stack = Stack::settle(swapped, Axis::X, 0);
+        let bottoms: Vec<u32> = stack.boxes().iter().map(|c| c.min[0]).collect();
+        assert_eq!(bottoms, [0, 1, 1, 2, 2, 3, 4]);
+        assert_eq!(stack.chain_reaction(0), 6);
+    }
+}
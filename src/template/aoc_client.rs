@@ -0,0 +1,634 @@
+/// Module that talks to the Advent of Code website directly.
+/// It downloads inputs and puzzle descriptions (converted to Markdown) and submits answers.
+use std::{
+    env,
+    fmt::Display,
+    fs, io,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use crate::template::config;
+use crate::{Day, Puzzle, Year};
+
+const BASE_URL: &str = "https://adventofcode.com";
+const USER_AGENT: &str = "github.com/FelixMaetzler/AOC_2023";
+const SESSION_FILE: &str = ".adventofcode.session";
+
+#[derive(Debug)]
+pub enum AocClientError {
+    MissingSession,
+    MissingYear,
+    Http { status: u16, url: String },
+    Transport(String),
+    IoError(io::Error),
+}
+
+impl Display for AocClientError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AocClientError::MissingSession => write!(
+                f,
+                "no session cookie found. Set AOC_SESSION or create \"~/{SESSION_FILE}\"."
+            ),
+            AocClientError::MissingYear => {
+                write!(f, "no year configured. Set `year` in aoc.toml or AOC_YEAR.")
+            }
+            AocClientError::Http { status: 400, url } => write!(
+                f,
+                "request to {url} was rejected (400). The session cookie might be expired."
+            ),
+            AocClientError::Http { status: 404, url } => write!(
+                f,
+                "{url} was not found (404). The puzzle might not be unlocked yet."
+            ),
+            AocClientError::Http { status, url } => {
+                write!(f, "request to {url} failed with status {status}.")
+            }
+            AocClientError::Transport(e) => write!(f, "could not reach Advent of Code: {e}"),
+            AocClientError::IoError(e) => {
+                write!(f, "could not write output files to file system: {e}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for AocClientError {}
+
+impl From<io::Error> for AocClientError {
+    fn from(e: io::Error) -> Self {
+        AocClientError::IoError(e)
+    }
+}
+
+impl From<ureq::Error> for AocClientError {
+    fn from(e: ureq::Error) -> Self {
+        match e {
+            ureq::Error::Status(status, response) => AocClientError::Http {
+                status,
+                url: response.get_url().to_string(),
+            },
+            ureq::Error::Transport(e) => AocClientError::Transport(e.to_string()),
+        }
+    }
+}
+
+/// The outcome of a submitted answer as reported by the website.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    Correct,
+    Incorrect,
+    TooHigh,
+    TooLow,
+    /// An answer was submitted too recently.
+    RateLimited,
+    /// The part was already solved or is not unlocked yet.
+    WrongLevel,
+    Unknown,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubmissionResponse {
+    pub verdict: Verdict,
+    /// How long to wait before the next submission, if the website asks for it.
+    pub wait: Option<Duration>,
+    /// The text of the response without markup.
+    pub message: String,
+}
+
+impl Display for SubmissionResponse {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let verdict = match self.verdict {
+            Verdict::Correct => "⭐ That's the right answer!",
+            Verdict::Incorrect => "✖ That's not the right answer.",
+            Verdict::TooHigh => "✖ That's not the right answer, it is too high.",
+            Verdict::TooLow => "✖ That's not the right answer, it is too low.",
+            Verdict::RateLimited => "⏳ You gave an answer too recently.",
+            Verdict::WrongLevel => "✖ This part is already solved or not unlocked yet.",
+            Verdict::Unknown => "? Unexpected response.",
+        };
+        write!(f, "{verdict}")?;
+        if let Some(wait) = self.wait {
+            write!(f, " Wait {}s before submitting again.", wait.as_secs())?;
+        }
+        Ok(())
+    }
+}
+
+/// A client for one year of Advent of Code, authenticated with a session cookie.
+pub struct AocClient {
+    agent: ureq::Agent,
+    base_url: String,
+    session: String,
+    year: Year,
+}
+
+impl AocClient {
+    #[must_use]
+    pub fn new(session: impl Into<String>, year: Year) -> Self {
+        Self {
+            agent: ureq::AgentBuilder::new()
+                .user_agent(USER_AGENT)
+                .timeout(Duration::from_secs(30))
+                .build(),
+            base_url: BASE_URL.into(),
+            session: session.into(),
+            year,
+        }
+    }
+
+    /// Creates a client from the session cookie in the environment and the configured year.
+    pub fn from_env() -> Result<Self, AocClientError> {
+        Self::for_year(get_year())
+    }
+
+    /// Creates a client from the session cookie in the environment for the year of `puzzle`.
+    pub fn for_puzzle(puzzle: Puzzle) -> Result<Self, AocClientError> {
+        Self::for_year(puzzle.resolve_year())
+    }
+
+    fn for_year(year: Option<Year>) -> Result<Self, AocClientError> {
+        let session = get_session().ok_or(AocClientError::MissingSession)?;
+        let year = year.ok_or(AocClientError::MissingYear)?;
+        Ok(Self::new(session, year))
+    }
+
+    /// Points the client at another server, e.g. a local stub in tests.
+    #[must_use]
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    fn day_url(&self, day: Day) -> String {
+        format!("{}/{}/day/{}", self.base_url, self.year, day.into_inner())
+    }
+
+    fn get(&self, url: &str) -> Result<String, AocClientError> {
+        let response = self
+            .agent
+            .get(url)
+            .set("Cookie", &format!("session={}", self.session))
+            .call()?;
+        Ok(response.into_string()?)
+    }
+
+    pub fn get_input(&self, day: Day) -> Result<String, AocClientError> {
+        self.get(&format!("{}/input", self.day_url(day)))
+    }
+
+    pub fn get_puzzle_html(&self, day: Day) -> Result<String, AocClientError> {
+        self.get(&self.day_url(day))
+    }
+
+    /// Fetches the puzzle description and converts it to Markdown.
+    pub fn get_puzzle(&self, day: Day) -> Result<String, AocClientError> {
+        Ok(puzzle_to_markdown(&self.get_puzzle_html(day)?))
+    }
+
+    pub fn submit(
+        &self,
+        day: Day,
+        part: u8,
+        answer: &str,
+    ) -> Result<SubmissionResponse, AocClientError> {
+        let response = self
+            .agent
+            .post(&format!("{}/answer", self.day_url(day)))
+            .set("Cookie", &format!("session={}", self.session))
+            .send_form(&[("level", &part.to_string()), ("answer", answer)])?;
+        Ok(parse_submission(&response.into_string()?))
+    }
+}
+
+pub fn read(puzzle: Puzzle) -> Result<String, AocClientError> {
+    let description = AocClient::for_puzzle(puzzle)?.get_puzzle(puzzle.day)?;
+    write_file(&get_puzzle_path(puzzle), &description)?;
+    Ok(description)
+}
+
+pub fn download(puzzle: Puzzle) -> Result<(), AocClientError> {
+    let client = AocClient::for_puzzle(puzzle)?;
+    let input_path = get_input_path(puzzle);
+    let puzzle_path = get_puzzle_path(puzzle);
+
+    write_file(&input_path, &client.get_input(puzzle.day)?)?;
+    write_file(&puzzle_path, &client.get_puzzle(puzzle.day)?)?;
+
+    let config = config::get();
+    println!(
+        "🎄 Successfully wrote input to \"{}\".",
+        config.relative(&input_path)
+    );
+    println!(
+        "🎄 Successfully wrote puzzle to \"{}\".",
+        config.relative(&puzzle_path)
+    );
+    Ok(())
+}
+
+pub fn submit(
+    puzzle: Puzzle,
+    part: u8,
+    result: &str,
+) -> Result<SubmissionResponse, AocClientError> {
+    AocClient::for_puzzle(puzzle)?.submit(puzzle.day, part, result)
+}
+
+/// Writes a file, creating the per-year data directories if needed.
+fn write_file(path: &Path, contents: &str) -> Result<(), io::Error> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, contents)
+}
+
+fn get_input_path(puzzle: Puzzle) -> PathBuf {
+    config::get().input_path(puzzle)
+}
+
+fn get_puzzle_path(puzzle: Puzzle) -> PathBuf {
+    config::get().puzzle_path(puzzle)
+}
+
+pub fn get_year() -> Option<Year> {
+    config::get().year()
+}
+
+/// Reads the session cookie from `AOC_SESSION` or from `~/.adventofcode.session`.
+pub fn get_session() -> Option<String> {
+    let session = match env::var("AOC_SESSION") {
+        Ok(x) => x,
+        Err(_) => {
+            let home = env::var_os("HOME").or_else(|| env::var_os("USERPROFILE"))?;
+            fs::read_to_string(PathBuf::from(home).join(SESSION_FILE)).ok()?
+        }
+    };
+    let session = session.trim();
+    (!session.is_empty()).then(|| session.to_string())
+}
+
+/* -------------------------------------------------------------------------- */
+
+fn parse_submission(html: &str) -> SubmissionResponse {
+    let article = extract_articles(html).next().unwrap_or(html);
+    let message = strip_tags(article);
+
+    let verdict = if message.contains("That's the right answer") {
+        Verdict::Correct
+    } else if message.contains("answer is too high") {
+        Verdict::TooHigh
+    } else if message.contains("answer is too low") {
+        Verdict::TooLow
+    } else if message.contains("That's not the right answer") {
+        Verdict::Incorrect
+    } else if message.contains("You gave an answer too recently") {
+        Verdict::RateLimited
+    } else if message.contains("solving the right level") {
+        Verdict::WrongLevel
+    } else {
+        Verdict::Unknown
+    };
+
+    SubmissionResponse {
+        verdict,
+        wait: parse_wait(&message),
+        message,
+    }
+}
+
+/// Parses phrases like "you have 1m 5s left to wait" or "please wait 5 minutes before trying again".
+fn parse_wait(message: &str) -> Option<Duration> {
+    if let Some(pos) = message.find(" left to wait") {
+        let before = &message[..pos];
+        let start = before.rfind("have ").map_or(0, |i| i + "have ".len());
+        let seconds = before[start..]
+            .split_ascii_whitespace()
+            .map(|token| {
+                let (i, unit) = token.char_indices().last()?;
+                let value: u64 = token[..i].parse().ok()?;
+                match unit {
+                    'h' => Some(value * 3600),
+                    'm' => Some(value * 60),
+                    's' => Some(value),
+                    _ => None,
+                }
+            })
+            .sum::<Option<u64>>()?;
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let pos = message.find("wait ")?;
+    let mut words = message[pos + "wait ".len()..].split_ascii_whitespace();
+    let amount = match words.next()? {
+        "one" | "a" => 1,
+        "two" => 2,
+        "three" => 3,
+        "four" => 4,
+        "five" => 5,
+        "ten" => 10,
+        x => x.parse().ok()?,
+    };
+    let seconds = match words.next()? {
+        unit if unit.starts_with("second") => amount,
+        unit if unit.starts_with("minute") => amount * 60,
+        unit if unit.starts_with("hour") => amount * 3600,
+        _ => return None,
+    };
+    Some(Duration::from_secs(seconds))
+}
+
+fn extract_articles(html: &str) -> impl Iterator<Item = &str> {
+    html.split("<article").skip(1).filter_map(|part| {
+        let start = part.find('>')? + 1;
+        let end = part.find("</article>")?;
+        Some(&part[start..end])
+    })
+}
+
+fn decode_entities(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(pos) = rest.find('&') {
+        out.push_str(&rest[..pos]);
+        rest = &rest[pos..];
+        let Some(end) = rest.find(';').filter(|&end| end <= 8) else {
+            out.push('&');
+            rest = &rest[1..];
+            continue;
+        };
+        let decoded = match &rest[1..end] {
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "amp" => Some('&'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "nbsp" => Some(' '),
+            entity => entity
+                .strip_prefix('#')
+                .and_then(|n| match n.strip_prefix('x') {
+                    Some(hex) => u32::from_str_radix(hex, 16).ok(),
+                    None => n.parse().ok(),
+                })
+                .and_then(char::from_u32),
+        };
+        match decoded {
+            Some(ch) => {
+                out.push(ch);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// A tag of the form `<name attr="value">` or `</name>`.
+struct Tag<'a> {
+    name: &'a str,
+    closing: bool,
+    attributes: &'a str,
+}
+
+impl<'a> Tag<'a> {
+    fn parse(tag: &'a str) -> Self {
+        let tag = tag.trim_end_matches('/');
+        let (closing, tag) = match tag.strip_prefix('/') {
+            Some(tag) => (true, tag),
+            None => (false, tag),
+        };
+        let (name, attributes) = tag.split_once(char::is_whitespace).unwrap_or((tag, ""));
+        Self {
+            name,
+            closing,
+            attributes,
+        }
+    }
+
+    fn attribute(&self, key: &str) -> Option<&'a str> {
+        let start = self.attributes.find(&format!("{key}=\""))? + key.len() + 2;
+        let len = self.attributes[start..].find('"')?;
+        Some(&self.attributes[start..start + len])
+    }
+}
+
+/// Splits html into text and tags. Text is returned as `Err`, tags as `Ok`.
+fn tokenize(html: &str) -> impl Iterator<Item = Result<Tag<'_>, &str>> {
+    let mut rest = html;
+    std::iter::from_fn(move || {
+        if rest.is_empty() {
+            return None;
+        }
+        if let Some(tag) = rest.strip_prefix('<') {
+            if let Some(end) = tag.find('>') {
+                rest = &tag[end + 1..];
+                return Some(Ok(Tag::parse(&tag[..end])));
+            }
+        }
+        let end = rest[1..].find('<').map_or(rest.len(), |i| i + 1);
+        let text = &rest[..end];
+        rest = &rest[end..];
+        Some(Err(text))
+    })
+}
+
+fn strip_tags(html: &str) -> String {
+    let text: String = tokenize(html).filter_map(Result::err).collect();
+    decode_entities(&text).trim().to_string()
+}
+
+/// Converts the puzzle descriptions of an Advent of Code page to Markdown.
+fn puzzle_to_markdown(html: &str) -> String {
+    let mut out = String::new();
+    let mut in_pre = false;
+    let mut in_code = false;
+    let mut links: Vec<&str> = vec![];
+
+    for article in extract_articles(html) {
+        for token in tokenize(article) {
+            let tag = match token {
+                // skip whitespace between block elements.
+                Err(text) if !in_pre && text.trim().is_empty() && out.ends_with('\n') => continue,
+                Err(text) => {
+                    out.push_str(&decode_entities(text));
+                    continue;
+                }
+                Ok(tag) => tag,
+            };
+            match (tag.name, tag.closing) {
+                ("h2", false) => out.push_str("## "),
+                ("h2" | "p", true) => out.push_str("\n\n"),
+                ("ul", true) => out.push('\n'),
+                ("pre", false) => {
+                    in_pre = true;
+                    out.push_str("```\n");
+                }
+                ("pre", true) => {
+                    in_pre = false;
+                    if !out.ends_with('\n') {
+                        out.push('\n');
+                    }
+                    out.push_str("```\n\n");
+                }
+                ("code", closing) if !in_pre => {
+                    in_code = !closing;
+                    out.push('`');
+                }
+                ("em", _) if !in_pre && !in_code => out.push('*'),
+                ("li", false) => out.push_str("- "),
+                ("li", true) | ("br", _) => out.push('\n'),
+                ("a", false) => {
+                    links.push(tag.attribute("href").unwrap_or_default());
+                    out.push('[');
+                }
+                ("a", true) => {
+                    out.push_str(&format!("]({})", links.pop().unwrap_or_default()));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let mut markdown = out.trim_end().to_string();
+    markdown.push('\n');
+    markdown
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(all(test, feature = "test_lib"))]
+mod tests {
+    use super::{parse_submission, parse_wait, puzzle_to_markdown, AocClient, Verdict};
+    use crate::{day, year};
+    use std::{
+        io::{BufRead, BufReader, Read, Write},
+        net::TcpListener,
+        sync::mpsc,
+        thread,
+        time::Duration,
+    };
+
+    /// Serves a single response on a local port and reports the received request.
+    fn stub_server(status: u16, body: &'static str) -> (String, mpsc::Receiver<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let (tx, rx) = mpsc::channel();
+
+        thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut request = String::new();
+            let mut content_length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if let Some(len) = line.to_lowercase().strip_prefix("content-length:") {
+                    content_length = len.trim().parse().unwrap();
+                }
+                request.push_str(&line);
+                if line == "\r\n" {
+                    break;
+                }
+            }
+            let mut body_buf = vec![0; content_length];
+            reader.read_exact(&mut body_buf).unwrap();
+            request.push_str(&String::from_utf8(body_buf).unwrap());
+
+            let response = format!(
+                "HTTP/1.1 {status} Stub\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            );
+            reader.get_mut().write_all(response.as_bytes()).unwrap();
+            tx.send(request).unwrap();
+        });
+
+        (url, rx)
+    }
+
+    #[test]
+    fn fetches_input() {
+        let (url, rx) = stub_server(200, "1abc2\npqr3stu8vwx\n");
+        let client = AocClient::new("secret", year!(2023)).with_base_url(url);
+
+        let input = client.get_input(day!(1)).unwrap();
+        let request = rx.recv().unwrap();
+
+        assert_eq!(input, "1abc2\npqr3stu8vwx\n");
+        assert!(request.starts_with("GET /2023/day/1/input HTTP/1.1"));
+        assert!(request.contains("session=secret"));
+    }
+
+    #[test]
+    fn reports_http_errors() {
+        let (url, _rx) = stub_server(404, "Not Found");
+        let client = AocClient::new("secret", year!(2023)).with_base_url(url);
+
+        let err = client.get_input(day!(25)).unwrap_err();
+        assert!(err.to_string().contains("404"));
+    }
+
+    #[test]
+    fn submits_answer() {
+        let (url, rx) = stub_server(
+            200,
+            "<main><article><p>That's the right answer! You are <em>one gold star</em> closer.</p></article></main>",
+        );
+        let client = AocClient::new("secret", year!(2023)).with_base_url(url);
+
+        let response = client.submit(day!(3), 2, "4361").unwrap();
+        let request = rx.recv().unwrap();
+
+        assert_eq!(response.verdict, Verdict::Correct);
+        assert!(request.starts_with("POST /2023/day/3/answer HTTP/1.1"));
+        assert!(request.ends_with("level=2&answer=4361"));
+    }
+
+    #[test]
+    fn parses_submission_responses() {
+        let response = parse_submission("<article><p>That's not the right answer; your answer is too high.  If you're stuck, make sure you're using the full input data; there are also some general tips on the <a href=\"/2023/about\">about page</a>, or you can ask for hints on the <a href=\"https://www.reddit.com/r/adventofcode/\" target=\"_blank\">subreddit</a>.  Please wait one minute before trying again. <a href=\"/2023/day/1\">[Return to Day 1]</a></p></article>");
+        assert_eq!(response.verdict, Verdict::TooHigh);
+        assert_eq!(response.wait, Some(Duration::from_secs(60)));
+
+        let response = parse_submission("<article><p>That's not the right answer; your answer is too low. Please wait 5 minutes before trying again.</p></article>");
+        assert_eq!(response.verdict, Verdict::TooLow);
+        assert_eq!(response.wait, Some(Duration::from_secs(300)));
+
+        let response = parse_submission("<article><p>You gave an answer too recently; you have to wait after submitting an answer before trying again.  You have 1m 5s left to wait. <a href=\"/2023/day/1\">[Return to Day 1]</a></p></article>");
+        assert_eq!(response.verdict, Verdict::RateLimited);
+        assert_eq!(response.wait, Some(Duration::from_secs(65)));
+
+        let response = parse_submission("<article><p>You don't seem to be solving the right level.  Did you already complete it?</p></article>");
+        assert_eq!(response.verdict, Verdict::WrongLevel);
+        assert_eq!(response.wait, None);
+    }
+
+    #[test]
+    fn parses_wait_times() {
+        assert_eq!(
+            parse_wait("You have 35s left to wait."),
+            Some(Duration::from_secs(35))
+        );
+        assert_eq!(
+            parse_wait("please wait 10 seconds"),
+            Some(Duration::from_secs(10))
+        );
+        assert_eq!(parse_wait("nothing"), None);
+        assert_eq!(parse_wait("You have 5é left to wait."), None);
+        assert_eq!(parse_wait("You have 5d left to wait."), None);
+    }
+
+    #[test]
+    fn converts_puzzle_to_markdown() {
+        let html = r#"<html><main><article class="day-desc"><h2>--- Day 1: Trebuchet?! ---</h2><p>Something is <em>wrong</em> with <a href="/2023/about">global</a> snow &lt;production&gt;.</p>
+<pre><code>1abc2
+<em>pqr3</em>stu8vwx
+</code></pre>
+<p>The answer is <code><em>142</em></code>.</p><ul><li>one</li><li>two</li></ul></article><p>Your puzzle answer was <code>54634</code>.</p></main></html>"#;
+        let expected = "## --- Day 1: Trebuchet?! ---\n\nSomething is *wrong* with [global](/2023/about) snow <production>.\n\n```\n1abc2\npqr3stu8vwx\n```\n\nThe answer is `142`.\n\n- one\n- two\n";
+        assert_eq!(puzzle_to_markdown(html), expected);
+    }
+}
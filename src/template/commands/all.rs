@@ -1,38 +1,193 @@
-use std::io;
+use std::{
+    collections::BTreeMap,
+    io,
+    path::{Path, PathBuf},
+    process,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc,
+    },
+    thread,
+};
 
 use crate::template::{
+    config::{self, LimitsConfig},
+    generate::Synthetic,
     readme_benchmarks::{self, Timings},
+    submissions::History,
     ANSI_BOLD, ANSI_ITALIC, ANSI_RESET,
 };
-use crate::{all_days, Day};
+use crate::{all_days, Day, Puzzle, Year};
+use child_commands::PartOutput;
+
+/// Selects the days and parts that `cargo all` runs.
+#[derive(Debug, Clone, Default)]
+pub struct Filter {
+    /// Only run these days, all days if [`None`].
+    pub days: Option<Vec<Day>>,
+    pub skip: Vec<Day>,
+    /// Only run this part, both parts if [`None`].
+    pub part: Option<u8>,
+    /// Only run days with an accepted answer in the submission history.
+    pub only_solved: bool,
+    /// Only print days that fail, panic, time out or no longer produce the accepted answer.
+    pub only_failing: bool,
+}
+
+impl Filter {
+    /// Whether the run covers a subset of the solutions only.
+    #[must_use]
+    pub fn is_partial(&self) -> bool {
+        self.days.is_some() || !self.skip.is_empty() || self.part.is_some() || self.only_solved
+    }
+
+    fn parts(&self) -> Vec<u8> {
+        self.part.map_or_else(|| vec![1, 2], |part| vec![part])
+    }
+
+    fn selects(&self, puzzle: Puzzle) -> bool {
+        if self
+            .days
+            .as_ref()
+            .is_some_and(|days| !days.contains(&puzzle.day))
+            || self.skip.contains(&puzzle.day)
+        {
+            return false;
+        }
+
+        !self.only_solved || {
+            let history = History::load(puzzle).unwrap_or_default();
+            self.parts()
+                .into_iter()
+                .any(|part| history.correct_answer(part).is_some())
+        }
+    }
 
-pub fn handle(is_release: bool, is_timed: bool) {
+    fn is_failing(&self, puzzle: Puzzle, run: &child_commands::Run) -> bool {
+        if run.timed_out || child_commands::parse_failed(&run.output) {
+            return true;
+        }
+
+        let history = History::load(puzzle).unwrap_or_default();
+        self.parts().into_iter().any(|part| {
+            let expected = history.correct_answer(part);
+            match child_commands::parse_part(&run.output, part) {
+                PartOutput::Failed => true,
+                PartOutput::Answer(answer) => expected.is_some_and(|e| e != answer),
+                PartOutput::NotImplemented => expected.is_some(),
+                PartOutput::Unknown => false,
+            }
+        })
+    }
+}
+
+/// Settings that are passed on to every solution bin.
+#[derive(Clone, Copy)]
+pub(crate) struct RunOptions<'a> {
+    is_release: bool,
+    is_timed: bool,
+    part: Option<u8>,
+    limits: &'a LimitsConfig,
+    synthetic: Option<Synthetic>,
+    /// The directory of the built solution bins, which are then spawned directly instead of
+    /// through `cargo run`. Killing `cargo run` only ends the solution where cargo replaces
+    /// itself with the bin.
+    bins: Option<&'a Path>,
+}
+
+/// Runs all solutions. With `jobs > 1`, untimed runs execute several days at once
+/// and print the buffered output of each day in order.
+/// With a `synthetic` input, the solutions run on generated inputs instead of the real ones.
+pub fn handle(
+    year: Option<Year>,
+    is_release: bool,
+    is_timed: bool,
+    jobs: usize,
+    limits: &LimitsConfig,
+    filter: &Filter,
+    synthetic: Option<Synthetic>,
+) {
     let mut timings: Vec<Timings> = vec![];
+    let puzzles: Vec<Puzzle> = all_days(year).filter(|&p| filter.selects(p)).collect();
+
+    // parallel runs would distort each other's timings.
+    let jobs = if is_timed && jobs > 1 {
+        eprintln!("Ignoring `--jobs {jobs}`: timed runs are executed sequentially.");
+        1
+    } else {
+        jobs.max(1)
+    };
 
-    all_days().for_each(|day| {
-        if day > 1 {
-            println!();
+    // a day timeout should not include compilation and parallel runs should not wait on the
+    // build lock, so all solutions are built upfront.
+    let bins = if limits.day_timeout.is_some() || jobs > 1 {
+        match child_commands::build_solutions(is_release) {
+            Ok(bins) => Some(bins),
+            Err(e) => {
+                eprintln!("Failed to build solutions: {e:?}");
+                process::exit(1);
+            }
         }
+    } else {
+        None
+    };
 
-        println!("{ANSI_BOLD}Day {day}{ANSI_RESET}");
-        println!("------");
+    let options = RunOptions {
+        is_release,
+        is_timed,
+        part: filter.part,
+        limits,
+        synthetic,
+        bins: bins.as_deref(),
+    };
 
-        let output = child_commands::run_solution(day, is_timed, is_release).unwrap();
+    if jobs == 1 && !filter.only_failing {
+        for (i, &puzzle) in puzzles.iter().enumerate() {
+            print_header(i, puzzle);
+            let run = child_commands::run_solution(puzzle, options, true).unwrap();
+            report(puzzle, &run, limits, &mut timings);
+        }
+    } else {
+        let mut printed = 0;
+        run_parallel(&puzzles, jobs, options, |puzzle, run| {
+            if filter.only_failing && !filter.is_failing(puzzle, &run) {
+                return;
+            }
+            print_header(printed, puzzle);
+            printed += 1;
+            run.output.iter().for_each(|line| println!("{line}"));
+            run.errors.iter().for_each(|line| eprintln!("{line}"));
+            report(puzzle, &run, limits, &mut timings);
+        });
 
-        if output.is_empty() {
-            println!("Not solved.");
-        } else {
-            let val = child_commands::parse_exec_time(&output, day);
-            timings.push(val);
+        if filter.only_failing && printed == 0 {
+            println!("No failing days.");
         }
-    });
+    }
+
+    let timed_out: Vec<String> = timings
+        .iter()
+        .filter(|t| t.timed_out)
+        .map(|t| t.puzzle.to_string())
+        .collect();
+
+    if !timed_out.is_empty() {
+        println!(
+            "\n{ANSI_BOLD}Timed out:{ANSI_RESET} {}",
+            timed_out.join(", ")
+        );
+    }
 
     if is_timed {
         let total_millis = timings.iter().map(|x| x.total_nanos).sum::<f64>() / 1_000_000_f64;
 
         println!("\n{ANSI_BOLD}Total:{ANSI_RESET} {ANSI_ITALIC}{total_millis:.2}ms{ANSI_RESET}");
 
-        if is_release {
+        if is_release && synthetic.is_some() {
+            println!("Skipped README update, the benchmarks use generated inputs.");
+        } else if is_release && (filter.is_partial() || filter.only_failing) {
+            println!("Skipped README update, the benchmarks only cover a subset of the solutions.");
+        } else if is_release {
             match readme_benchmarks::update(timings, total_millis) {
                 Ok(()) => println!("Successfully updated README with benchmarks."),
                 Err(_) => {
@@ -43,6 +198,75 @@ pub fn handle(is_release: bool, is_timed: bool) {
     }
 }
 
+/// Runs the solutions on `jobs` worker threads and calls `on_done` in the order of `puzzles`.
+fn run_parallel(
+    puzzles: &[Puzzle],
+    jobs: usize,
+    options: RunOptions,
+    mut on_done: impl FnMut(Puzzle, child_commands::Run),
+) {
+    let next = AtomicUsize::new(0);
+    let (sender, receiver) = mpsc::channel();
+
+    thread::scope(|scope| {
+        for _ in 0..jobs.min(puzzles.len()) {
+            let sender = sender.clone();
+            let next = &next;
+            scope.spawn(move || loop {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                let Some(&puzzle) = puzzles.get(i) else {
+                    break;
+                };
+                let run = child_commands::run_solution(puzzle, options, false).unwrap();
+                if sender.send((i, run)).is_err() {
+                    break;
+                }
+            });
+        }
+        drop(sender);
+
+        // buffer finished days until all previous days are done.
+        let mut pending = BTreeMap::new();
+        let mut printed = 0;
+        for (i, run) in receiver {
+            pending.insert(i, run);
+            while let Some(run) = pending.remove(&printed) {
+                on_done(puzzles[printed], run);
+                printed += 1;
+            }
+        }
+    });
+}
+
+fn print_header(i: usize, puzzle: Puzzle) {
+    if i > 0 {
+        println!();
+    }
+
+    println!("{ANSI_BOLD}{puzzle}{ANSI_RESET}");
+    println!("------");
+}
+
+fn report(
+    puzzle: Puzzle,
+    run: &child_commands::Run,
+    limits: &LimitsConfig,
+    timings: &mut Vec<Timings>,
+) {
+    if run.timed_out {
+        let timeout = limits.day_timeout.unwrap_or_default();
+        println!("✖ timed out after {timeout:.1?}");
+    }
+
+    if run.output.is_empty() && !run.timed_out {
+        println!("Not solved.");
+    } else {
+        let mut val = child_commands::parse_exec_time(&run.output, puzzle);
+        val.timed_out |= run.timed_out;
+        timings.push(val);
+    }
+}
+
 #[derive(Debug)]
 pub enum Error {
     BrokenPipe,
@@ -57,47 +281,128 @@ impl From<std::io::Error> for Error {
 }
 
 #[must_use]
-pub fn get_path_for_bin(day: Day) -> String {
-    format!("./src/bin/{day}.rs")
+pub fn get_path_for_bin(puzzle: Puzzle) -> PathBuf {
+    config::get().bin_path(puzzle)
 }
 
 /// All solutions live in isolated binaries.
 /// This module encapsulates interaction with these binaries, both invoking them as well as parsing the timing output.
-mod child_commands {
-    use super::{get_path_for_bin, Error};
-    use crate::Day;
+pub(crate) mod child_commands {
+    use super::{get_path_for_bin, Error, RunOptions};
+    use crate::template::readme_benchmarks::PartStats;
+    use crate::template::{config, ANSI_BOLD, ANSI_RESET};
+    use crate::Puzzle;
     use std::{
+        env::{self, consts::EXE_SUFFIX},
         io::{BufRead, BufReader},
-        path::Path,
+        path::{Path, PathBuf},
         process::{Command, Stdio},
         thread,
+        time::{Duration, Instant},
     };
 
-    /// Run the solution bin for a given day
-    pub fn run_solution(day: Day, is_timed: bool, is_release: bool) -> Result<Vec<String>, Error> {
-        // skip command invocation for days that have not been scaffolded yet.
-        if !Path::new(&get_path_for_bin(day)).exists() {
-            return Ok(vec![]);
-        }
-
-        let day_padded = day.to_string();
-        let mut args = vec!["run", "--quiet", "--bin", &day_padded];
+    /// The output of a solution bin.
+    pub struct Run {
+        pub output: Vec<String>,
+        /// The stderr lines of the solution, only collected if the output is not streamed.
+        pub errors: Vec<String>,
+        /// The process was killed because it exceeded the day timeout.
+        pub timed_out: bool,
+    }
 
+    /// Builds all solution bins and returns the directory they are built into.
+    pub fn build_solutions(is_release: bool) -> Result<PathBuf, Error> {
+        let mut args = vec!["build", "--quiet", "--bins"];
         if is_release {
             args.push("--release");
         }
 
+        let status = Command::new("cargo")
+            .args(&args)
+            .current_dir(&config::get().root)
+            .status()?;
+
+        if !status.success() {
+            return Err(Error::Parser(format!("cargo build exited with {status}")));
+        }
+
+        // this runner is itself a bin of the crate, built into a sibling of the solutions' directory.
+        let profile = if is_release { "release" } else { "debug" };
+        env::current_exe()?
+            .parent()
+            .and_then(Path::parent)
+            .map(|target| target.join(profile))
+            .filter(|bins| bins.is_dir())
+            .ok_or_else(|| Error::Parser("could not locate the built solution bins".into()))
+    }
+
+    /// Run the solution bin for a given puzzle.
+    /// If `stream` is set, its output is forwarded while it runs. Otherwise it is only collected.
+    pub fn run_solution(puzzle: Puzzle, options: RunOptions, stream: bool) -> Result<Run, Error> {
+        let RunOptions {
+            is_release,
+            is_timed,
+            part,
+            limits,
+            synthetic,
+            bins,
+        } = options;
+
+        // skip command invocation for days that have not been scaffolded yet.
+        if !get_path_for_bin(puzzle).exists() {
+            return Ok(Run {
+                output: vec![],
+                errors: vec![],
+                timed_out: false,
+            });
+        }
+
+        let bin_name = puzzle.bin_name();
+        let part_timeout = limits.part_timeout.map(|t| t.as_secs().to_string());
+        let part = part.map(|p| p.to_string());
+        let synthetic = synthetic.map(|s| s.args()).unwrap_or_default();
+        let mut args = vec![];
+
+        // mirror flags to child invocations.
         if is_timed {
-            // mirror `--time` flag to child invocations.
-            args.push("--");
             args.push("--time");
         }
 
+        if let Some(part_timeout) = &part_timeout {
+            args.push("--part-timeout");
+            args.push(part_timeout);
+        }
+
+        if limits.memory {
+            args.push("--memory");
+        }
+
+        if let Some(part) = &part {
+            args.push("--part");
+            args.push(part);
+        }
+
+        args.extend(synthetic.iter().map(String::as_str));
+
         // spawn child command with piped stdout/stderr.
         // forward output to stdout/stderr while grabbing stdout lines.
 
-        let mut cmd = Command::new("cargo")
+        let mut command = match bins {
+            Some(bins) => Command::new(bins.join(format!("{bin_name}{EXE_SUFFIX}"))),
+            None => {
+                let mut cargo = Command::new("cargo");
+                cargo.args(["run", "--quiet", "--bin", &bin_name]);
+                if is_release {
+                    cargo.arg("--release");
+                }
+                cargo.arg("--");
+                cargo
+            }
+        };
+
+        let mut cmd = command
             .args(&args)
+            .current_dir(&config::get().root)
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
             .spawn()?;
@@ -105,34 +410,123 @@ mod child_commands {
         let stdout = BufReader::new(cmd.stdout.take().ok_or(super::Error::BrokenPipe)?);
         let stderr = BufReader::new(cmd.stderr.take().ok_or(super::Error::BrokenPipe)?);
 
-        let mut output = vec![];
+        let stderr_thread = thread::spawn(move || {
+            let mut errors = vec![];
+            for line in stderr.lines() {
+                let line = line.unwrap();
+                if stream {
+                    eprintln!("{line}");
+                } else {
+                    errors.push(line);
+                }
+            }
+            errors
+        });
 
-        let thread = thread::spawn(move || {
-            stderr.lines().for_each(|line| {
-                eprintln!("{}", line.unwrap());
-            });
+        let stdout_thread = thread::spawn(move || {
+            let mut output = vec![];
+            for line in stdout.lines() {
+                let line = line.unwrap();
+                if stream {
+                    println!("{line}");
+                }
+                output.push(line);
+            }
+            output
         });
 
-        for line in stdout.lines() {
-            let line = line.unwrap();
-            println!("{line}");
-            output.push(line);
+        // poll the child so that it can be killed once the day timeout is exceeded.
+        let deadline = limits.day_timeout.map(|timeout| Instant::now() + timeout);
+        let mut timed_out = false;
+
+        while cmd.try_wait()?.is_none() {
+            if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                cmd.kill()?;
+                cmd.wait()?;
+                timed_out = true;
+                break;
+            }
+            thread::sleep(Duration::from_millis(10));
         }
 
-        thread.join().unwrap();
-        cmd.wait()?;
+        let output = stdout_thread.join().unwrap();
+        let errors = stderr_thread.join().unwrap();
+
+        Ok(Run {
+            output,
+            errors,
+            timed_out,
+        })
+    }
+
+    /// What a solution printed for one part.
+    #[derive(Debug, PartialEq, Eq)]
+    pub enum PartOutput {
+        Answer(String),
+        NotImplemented,
+        /// The part failed, panicked or timed out.
+        Failed,
+        /// The part did not print a (single-line) answer.
+        Unknown,
+    }
 
-        Ok(output)
+    pub fn parse_part(output: &[String], part: u8) -> PartOutput {
+        let prefix = format!("Part {part}: ");
+        // intermediate results are overwritten with `\r`, only the final result counts.
+        let Some(result) = output
+            .iter()
+            .filter_map(|l| l.rsplit('\r').next()?.strip_prefix(&prefix))
+            .next_back()
+        else {
+            return PartOutput::Unknown;
+        };
+
+        if let Some(reason) = result.strip_prefix('✖') {
+            if reason.trim().is_empty() {
+                PartOutput::NotImplemented
+            } else {
+                PartOutput::Failed
+            }
+        } else {
+            result
+                .strip_prefix(ANSI_BOLD)
+                .and_then(|r| r.split_once(ANSI_RESET))
+                .map_or(PartOutput::Unknown, |(answer, _)| {
+                    PartOutput::Answer(answer.into())
+                })
+        }
     }
 
-    pub fn parse_exec_time(output: &[String], day: Day) -> super::Timings {
+    /// Whether the `parse` function of the solution panicked.
+    pub fn parse_failed(output: &[String]) -> bool {
+        output.iter().any(|l| {
+            l.rsplit('\r')
+                .next()
+                .is_some_and(|l| l.starts_with("Parse: ✖"))
+        })
+    }
+
+    pub fn parse_exec_time(output: &[String], puzzle: Puzzle) -> super::Timings {
         let mut timings = super::Timings {
-            day,
+            puzzle,
             part_1: None,
             part_2: None,
+            parse: None,
             total_nanos: 0_f64,
+            timed_out: false,
+            peak_memory: None,
+            stats: [None, None],
         };
 
+        for line in output {
+            if line.contains("✖ timed out") {
+                timings.timed_out = true;
+            }
+            if let Some(bytes) = parse_memory(line) {
+                timings.peak_memory = Some(timings.peak_memory.map_or(bytes, |b| b.max(bytes)));
+            }
+        }
+
         output
             .iter()
             .filter_map(|l| {
@@ -146,13 +540,17 @@ mod child_commands {
                 };
 
                 let part = l.split(':').next()?;
-                Some((part, timing_str, nanos))
+                Some((part, timing_str, nanos, parse_stats(l)))
             })
-            .for_each(|(part, timing_str, nanos)| {
+            .for_each(|(part, timing_str, nanos, stats)| {
                 if part.contains("Part 1") {
                     timings.part_1 = Some(timing_str.into());
+                    timings.stats[0] = stats;
                 } else if part.contains("Part 2") {
                     timings.part_2 = Some(timing_str.into());
+                    timings.stats[1] = stats;
+                } else if part.contains("Parse") {
+                    timings.parse = Some(timing_str.into());
                 }
 
                 timings.total_nanos += nanos;
@@ -161,6 +559,35 @@ mod child_commands {
         timings
     }
 
+    /// Parses the peak memory printed by `--memory`, e.g. `[peak 3.4 MiB]`, in bytes.
+    fn parse_memory(line: &str) -> Option<f64> {
+        let memory = line.split(" [peak ").nth(1)?.split(']').next()?;
+        let (value, unit) = memory.split_once(' ')?;
+        let factor = match unit {
+            "KiB" => 1024_f64,
+            "MiB" => 1024_f64 * 1024_f64,
+            "GiB" => 1024_f64 * 1024_f64 * 1024_f64,
+            _ => return None,
+        };
+        Some(value.parse::<f64>().ok()? * factor)
+    }
+
+    /// Parses the standard deviation and the sample count of a benched part,
+    /// e.g. `(1.2ms ± 30.0µs @ 100 samples)`.
+    fn parse_stats(line: &str) -> Option<PartStats> {
+        let (timing, samples) = line
+            .split(" samples)")
+            .next()?
+            .rsplit('(')
+            .next()?
+            .split_once('@')?;
+
+        Some(PartStats {
+            samples: samples.trim().parse().ok()?,
+            std_dev: timing.split_once('±')?.1.trim().into(),
+        })
+    }
+
     fn parse_to_float(s: &str, postfix: &str) -> Option<f64> {
         s.split(postfix).next()?.parse().ok()
     }
@@ -171,9 +598,11 @@ mod child_commands {
             .split(" samples)")
             .next()?
             .split('(')
-            .last()?
+            .next_back()?
             .split('@')
             .next()?
+            .split('±')
+            .next()?
             .trim();
 
         let parsed_timing = match str_timing {
@@ -202,7 +631,7 @@ mod child_commands {
 
     #[cfg(feature = "test_lib")]
     mod tests {
-        use super::parse_exec_time;
+        use super::{parse_exec_time, parse_failed, parse_part, PartOutput};
 
         use crate::day;
 
@@ -214,13 +643,44 @@ mod child_commands {
                     "Part 2: 10 (74.13ms @ 99999 samples)".into(),
                     "".into(),
                 ],
-                day!(1),
+                day!(1).into(),
             );
             assert_approx_eq!(res.total_nanos, 74130074.13_f64);
             assert_eq!(res.part_1.unwrap(), "74.13ns");
             assert_eq!(res.part_2.unwrap(), "74.13ms");
         }
 
+        #[test]
+        fn test_std_dev() {
+            let res = parse_exec_time(
+                &[
+                    "Part 1: 0 (74.13ns ± 1.5ns @ 100000 samples)".into(),
+                    "Part 2: 10 (74.13ms @ 99999 samples)".into(),
+                ],
+                day!(1).into(),
+            );
+            assert_approx_eq!(res.total_nanos, 74130074.13_f64);
+            assert_eq!(res.part_1.unwrap(), "74.13ns");
+            let stats = res.stats[0].as_ref().unwrap();
+            assert_eq!(stats.samples, 100000);
+            assert_eq!(stats.std_dev, "1.5ns");
+            assert_eq!(res.stats[1], None);
+        }
+
+        #[test]
+        fn test_parse_time() {
+            let output = [
+                "Parse:  > benching\rParse: (2.0ms ± 0.1ms @ 500 samples)".into(),
+                "Part 1: 0 (1.0ms @ 1000 samples)".into(),
+            ];
+            let res = parse_exec_time(&output, day!(1).into());
+            assert_approx_eq!(res.total_nanos, 3000000_f64);
+            assert_eq!(res.parse.unwrap(), "2.0ms");
+            assert_eq!(res.part_1.unwrap(), "1.0ms");
+            assert!(!parse_failed(&output));
+            assert!(parse_failed(&["Parse: \rParse: ✖ panicked".into()]));
+        }
+
         #[test]
         fn test_patterns_in_input() {
             let res = parse_exec_time(
@@ -229,13 +689,48 @@ mod child_commands {
                     "Part 2: 10s (100ms @ 1 samples)".into(),
                     "".into(),
                 ],
-                day!(1),
+                day!(1).into(),
             );
             assert_approx_eq!(res.total_nanos, 2100000000_f64);
             assert_eq!(res.part_1.unwrap(), "2s");
             assert_eq!(res.part_2.unwrap(), "100ms");
         }
 
+        #[test]
+        fn test_timeouts_and_memory() {
+            let res = parse_exec_time(
+                &[
+                    "Part 1: 0 (74.13ns @ 100000 samples) [peak 2.0 MiB]".into(),
+                    "Part 2: ✖ timed out after 30.0s".into(),
+                ],
+                day!(1).into(),
+            );
+            assert_approx_eq!(res.total_nanos, 74.13_f64);
+            assert_eq!(res.part_1.unwrap(), "74.13ns");
+            assert_eq!(res.part_2, None);
+            assert!(res.timed_out);
+            assert_approx_eq!(res.peak_memory.unwrap(), 2_097_152_f64);
+        }
+
+        #[test]
+        fn test_parse_part() {
+            let output = [
+                "Part 1: \x1b[1m42\x1b[0m > benching\rPart 1: \x1b[1m42\x1b[0m (1.0ms)".into(),
+                "Part 2: ✖ panicked".into(),
+            ];
+            assert_eq!(parse_part(&output, 1), PartOutput::Answer("42".into()));
+            assert_eq!(parse_part(&output, 2), PartOutput::Failed);
+            assert_eq!(
+                parse_part(&["Part 2: ✖             ".into()], 2),
+                PartOutput::NotImplemented
+            );
+            assert_eq!(
+                parse_part(&["Part 1: ▼ (1.0ms)".into()], 1),
+                PartOutput::Unknown
+            );
+            assert_eq!(parse_part(&[], 1), PartOutput::Unknown);
+        }
+
         #[test]
         fn test_missing_parts() {
             let res = parse_exec_time(
@@ -244,7 +739,7 @@ mod child_commands {
                     "Part 2: ✖        ".into(),
                     "".into(),
                 ],
-                day!(1),
+                day!(1).into(),
             );
             assert_approx_eq!(res.total_nanos, 0_f64);
             assert_eq!(res.part_1.is_none(), true);
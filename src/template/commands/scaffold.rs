@@ -1,18 +1,26 @@
 use std::{
-    fs::{File, OpenOptions},
+    fs::{self, File, OpenOptions},
     io::Write,
+    path::Path,
     process,
 };
 
-use crate::Day;
+use crate::template::config;
+use crate::{Puzzle, Year};
 
-const MODULE_TEMPLATE: &str = r#"advent_of_code::solution!(DAY_NUMBER);
+/// Directory that holds user-defined module templates, e.g. `.template/day.rs`.
+const TEMPLATE_DIR: &str = ".template";
+/// Name of the template that is used if no `--template` is passed.
+const DEFAULT_TEMPLATE: &str = "day";
+const DEFAULT_ANSWER_TYPE: &str = "u32";
 
-pub fn part_one(input: &str) -> Option<u32> {
+const MODULE_TEMPLATE: &str = r#"advent_of_code::solution!(PUZZLE_ID);
+
+pub fn part_one(input: &str) -> Option<ANSWER_TYPE> {
     None
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
+pub fn part_two(input: &str) -> Option<ANSWER_TYPE> {
     None
 }
 
@@ -22,46 +30,236 @@ mod tests {
 
     #[test]
     fn test_part_one() {
-        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, None);
+        let mut checked = 0;
+        for example in advent_of_code::template::examples(DAY, 1) {
+            let Some(expected) = example.expected(1) else {
+                continue;
+            };
+            let result = part_one(&example.input).map(|r| r.to_string());
+            assert_eq!(result.as_deref(), Some(expected), "example {}", example.name);
+            checked += 1;
+        }
+        assert!(checked > 0, "no example declares an answer for part 1");
     }
 
     #[test]
     fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, None);
+        let mut checked = 0;
+        for example in advent_of_code::template::examples(DAY, 2) {
+            let Some(expected) = example.expected(2) else {
+                continue;
+            };
+            let result = part_two(&example.input).map(|r| r.to_string());
+            assert_eq!(result.as_deref(), Some(expected), "example {}", example.name);
+            checked += 1;
+        }
+        assert!(checked > 0, "no example declares an answer for part 2");
     }
 }
 "#;
 
-fn safe_create_file(path: &str) -> Result<File, std::io::Error> {
+const GRID_TEMPLATE: &str = r#"use advent_of_code::Grid;
+
+advent_of_code::solution!(PUZZLE_ID);
+
+pub fn part_one(input: &str) -> Option<ANSWER_TYPE> {
+    let _grid = parse(input);
+    None
+}
+
+pub fn part_two(input: &str) -> Option<ANSWER_TYPE> {
+    let _grid = parse(input);
+    None
+}
+
+fn parse(input: &str) -> Grid<char> {
+    Grid::from_iter_iter(input.trim().lines().map(|l| l.chars()))
+}
+"#;
+
+const LINES_TEMPLATE: &str = r#"advent_of_code::solution!(PUZZLE_ID);
+
+pub fn part_one(input: &str) -> Option<ANSWER_TYPE> {
+    let _lines = parse(input);
+    None
+}
+
+pub fn part_two(input: &str) -> Option<ANSWER_TYPE> {
+    let _lines = parse(input);
+    None
+}
+
+fn parse(input: &str) -> Vec<&str> {
+    input.trim().lines().map(parse_line).collect()
+}
+
+fn parse_line(line: &str) -> &str {
+    line
+}
+"#;
+
+const GRAPH_TEMPLATE: &str = r#"use std::collections::HashMap;
+
+advent_of_code::solution!(PUZZLE_ID);
+
+pub fn part_one(input: &str) -> Option<ANSWER_TYPE> {
+    let _graph = parse(input);
+    None
+}
+
+pub fn part_two(input: &str) -> Option<ANSWER_TYPE> {
+    let _graph = parse(input);
+    None
+}
+
+fn parse(input: &str) -> HashMap<&str, Vec<&str>> {
+    input
+        .trim()
+        .lines()
+        .map(|l| {
+            let (node, neighbours) = l.split_once(':').unwrap();
+            (node.trim(), neighbours.split_ascii_whitespace().collect())
+        })
+        .collect()
+}
+"#;
+
+/// Returns the built-in template with the given name.
+/// The grid, line-based and graph variants share the tests of the default template.
+fn builtin_template(name: &str) -> Option<String> {
+    let tests = &MODULE_TEMPLATE[MODULE_TEMPLATE.find("#[cfg(test)]")?..];
+    match name {
+        "day" | "default" => Some(MODULE_TEMPLATE.into()),
+        "grid" => Some(format!("{GRID_TEMPLATE}\n{tests}")),
+        "lines" => Some(format!("{LINES_TEMPLATE}\n{tests}")),
+        "graph" => Some(format!("{GRAPH_TEMPLATE}\n{tests}")),
+        _ => None,
+    }
+}
+
+/// Loads a module template. A file in `.template/` takes precedence over a built-in template of the same name,
+/// a value containing a path separator or ending in `.rs` is read as a path.
+fn load_template(name: &str) -> Result<String, String> {
+    if name.ends_with(".rs") || name.contains(['/', '\\']) {
+        return fs::read_to_string(name).map_err(|e| format!("could not read \"{name}\": {e}"));
+    }
+
+    let user_path = config::get()
+        .root
+        .join(TEMPLATE_DIR)
+        .join(format!("{name}.rs"));
+    if user_path.exists() {
+        return fs::read_to_string(&user_path)
+            .map_err(|e| format!("could not read \"{}\": {e}", user_path.display()));
+    }
+
+    builtin_template(name).ok_or_else(|| {
+        format!("unknown template \"{name}\", expecting one of: day, grid, lines, graph or a file in \"{TEMPLATE_DIR}/\"")
+    })
+}
+
+/// Values that are substituted into a template.
+struct Placeholders<'a> {
+    puzzle: Puzzle,
+    year: Option<Year>,
+    title: Option<&'a str>,
+    answer_type: &'a str,
+}
+
+/// Replaces the placeholders `PUZZLE_ID`, `DAY_NUMBER`, `DAY_PADDED`, `PUZZLE_YEAR`, `PUZZLE_TITLE` and `ANSWER_TYPE`.
+/// `PUZZLE_ID` is the argument of `solution!`, i.e. `3` or `2022, 3` for puzzles outside the flat layout.
+fn render(template: &str, placeholders: &Placeholders) -> String {
+    let day = placeholders.puzzle.day;
+    let puzzle_id = match placeholders.puzzle.year {
+        Some(year) => format!("{year}, {}", day.into_inner()),
+        None => day.into_inner().to_string(),
+    };
+    template
+        .replace("PUZZLE_ID", &puzzle_id)
+        .replace("DAY_NUMBER", &day.into_inner().to_string())
+        .replace("DAY_PADDED", &day.to_string())
+        .replace(
+            "PUZZLE_YEAR",
+            &placeholders.year.map(|y| y.to_string()).unwrap_or_default(),
+        )
+        .replace("PUZZLE_TITLE", placeholders.title.unwrap_or_default())
+        .replace("ANSWER_TYPE", placeholders.answer_type)
+}
+
+/// Extracts the puzzle title from a downloaded puzzle description, e.g. `--- Day 1: Trebuchet?! ---`.
+fn parse_title(puzzle: &str) -> Option<&str> {
+    let line = puzzle.lines().find(|l| l.contains("--- Day "))?;
+    let (_, title) = line.split_once(": ")?;
+    Some(title.split(" ---").next()?.trim())
+}
+
+fn safe_create_file(path: &Path) -> Result<File, std::io::Error> {
     OpenOptions::new().write(true).create_new(true).open(path)
 }
 
-fn create_file(path: &str) -> Result<File, std::io::Error> {
-    OpenOptions::new().write(true).create(true).open(path)
+fn create_file(path: &Path) -> Result<File, std::io::Error> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(path)
 }
 
-pub fn handle(day: Day) {
-    let input_path = format!("data/inputs/{day}.txt");
-    let example_path = format!("data/examples/{day}.txt");
-    let module_path = format!("src/bin/{day}.rs");
+fn overwrite_file(path: &Path) -> Result<File, std::io::Error> {
+    OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(path)
+}
+
+pub fn handle(puzzle: Puzzle, template: Option<&str>, answer_type: Option<&str>, force: bool) {
+    let config = config::get();
+    let input_path = config.input_path(puzzle);
+    let example_path = config.example_path(puzzle);
+    let puzzle_path = config.puzzle_path(puzzle);
+    let module_path = config.bin_path(puzzle);
+
+    let template = match load_template(template.unwrap_or(DEFAULT_TEMPLATE)) {
+        Ok(template) => template,
+        Err(e) => {
+            eprintln!("Failed to load template: {e}");
+            process::exit(1);
+        }
+    };
 
-    let mut file = match safe_create_file(&module_path) {
+    let description = fs::read_to_string(puzzle_path).unwrap_or_default();
+    let placeholders = Placeholders {
+        puzzle,
+        year: puzzle.resolve_year(),
+        title: parse_title(&description),
+        answer_type: answer_type.unwrap_or(DEFAULT_ANSWER_TYPE),
+    };
+
+    // an existing solution is only replaced if `--force` is passed.
+    let file = if force {
+        overwrite_file(&module_path)
+    } else {
+        safe_create_file(&module_path)
+    };
+
+    let mut file = match file {
         Ok(file) => file,
         Err(e) => {
             eprintln!("Failed to create module file: {e}");
+            if e.kind() == std::io::ErrorKind::AlreadyExists {
+                eprintln!("Pass `--force` to overwrite the existing solution.");
+            }
             process::exit(1);
         }
     };
 
-    match file.write_all(
-        MODULE_TEMPLATE
-            .replace("DAY_NUMBER", &day.into_inner().to_string())
-            .as_bytes(),
-    ) {
+    match file.write_all(render(&template, &placeholders).as_bytes()) {
         Ok(()) => {
-            println!("Created module file \"{}\"", &module_path);
+            println!("Created module file \"{}\"", config.relative(&module_path));
         }
         Err(e) => {
             eprintln!("Failed to write module contents: {e}");
@@ -71,7 +269,10 @@ pub fn handle(day: Day) {
 
     match create_file(&input_path) {
         Ok(_) => {
-            println!("Created empty input file \"{}\"", &input_path);
+            println!(
+                "Created empty input file \"{}\"",
+                config.relative(&input_path)
+            );
         }
         Err(e) => {
             eprintln!("Failed to create input file: {e}");
@@ -81,7 +282,10 @@ pub fn handle(day: Day) {
 
     match create_file(&example_path) {
         Ok(_) => {
-            println!("Created empty example file \"{}\"", &example_path);
+            println!(
+                "Created empty example file \"{}\"",
+                config.relative(&example_path)
+            );
         }
         Err(e) => {
             eprintln!("Failed to create example file: {e}");
@@ -90,5 +294,73 @@ pub fn handle(day: Day) {
     }
 
     println!("---");
-    println!("🎄 Type `cargo solve {}` to run your solution.", day);
+    match puzzle.year {
+        Some(year) => println!(
+            "🎄 Type `cargo solve {} --year {year}` to run your solution.",
+            puzzle.day
+        ),
+        None => println!("🎄 Type `cargo solve {}` to run your solution.", puzzle.day),
+    }
+}
+
+#[cfg(all(test, feature = "test_lib"))]
+mod tests {
+    use super::{builtin_template, parse_title, render, Placeholders};
+    use crate::{day, year, Puzzle};
+
+    #[test]
+    fn renders_placeholders() {
+        let placeholders = Placeholders {
+            puzzle: day!(3).into(),
+            year: Some(year!(2023)),
+            title: Some("Gear Ratios"),
+            answer_type: "u64",
+        };
+        let rendered = render(
+            "// PUZZLE_YEAR DAY_PADDED: PUZZLE_TITLE\nsolution!(DAY_NUMBER) -> ANSWER_TYPE",
+            &placeholders,
+        );
+        assert_eq!(rendered, "// 2023 03: Gear Ratios\nsolution!(3) -> u64");
+    }
+
+    #[test]
+    fn renders_puzzle_id_of_other_years() {
+        let placeholders = Placeholders {
+            puzzle: Puzzle::new(year!(2022), day!(3)),
+            year: Some(year!(2022)),
+            title: None,
+            answer_type: "u32",
+        };
+        assert_eq!(
+            render("solution!(PUZZLE_ID);", &placeholders),
+            "solution!(2022, 3);"
+        );
+    }
+
+    #[test]
+    fn builtin_variants_contain_tests() {
+        for name in ["day", "grid", "lines", "graph"] {
+            let template = builtin_template(name).unwrap();
+            assert!(template.contains("solution!(PUZZLE_ID)"));
+            assert!(template.contains("fn test_part_two()"));
+            assert!(template.contains("no example declares an answer for part 2"));
+        }
+        assert!(builtin_template("grid")
+            .unwrap()
+            .contains("Grid::from_iter_iter"));
+        assert!(builtin_template("unknown").is_none());
+    }
+
+    #[test]
+    fn parses_title() {
+        assert_eq!(
+            parse_title("\\--- Day 1: Trebuchet?! ---\n---------\n"),
+            Some("Trebuchet?!")
+        );
+        assert_eq!(
+            parse_title("## --- Day 12: Hot Springs ---"),
+            Some("Hot Springs")
+        );
+        assert_eq!(parse_title(""), None);
+    }
 }
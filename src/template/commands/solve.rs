@@ -1,27 +1,78 @@
-use std::process::{Command, Stdio};
+use std::{
+    io::{BufRead, BufReader},
+    path::Path,
+    process::{self, Command, Stdio},
+};
 
-use crate::Day;
+use crate::template::commands::all::child_commands::{parse_part, PartOutput};
+use crate::template::config;
+use crate::template::generate::Synthetic;
+use crate::template::watch::Watcher;
+use crate::template::{ANSI_BOLD, ANSI_RESET};
+use crate::Puzzle;
 
-pub fn handle(day: Day, release: bool, time: bool, submit_part: Option<u8>) {
-    let mut cmd_args = vec!["run".to_string(), "--bin".to_string(), day.to_string()];
+/// Settings of `cargo solve` besides the puzzle.
+#[derive(Debug, Clone, Default)]
+pub struct SolveOptions {
+    pub release: bool,
+    pub time: bool,
+    /// Profiles every part after it ran.
+    pub profile: bool,
+    pub submit: Option<u8>,
+    /// Runs the solution once per scale on a generated input instead of the real one.
+    pub synthetic: Vec<Synthetic>,
+    /// Re-runs the tests and the solution whenever a file of the day changes.
+    pub watch: bool,
+}
 
-    if release {
+/// Runs a solution with the settings of [`SolveOptions`].
+pub fn handle(puzzle: Puzzle, options: &SolveOptions) {
+    let mut cmd_args = vec!["run".to_string(), "--bin".to_string(), puzzle.bin_name()];
+
+    if options.release {
         cmd_args.push("--release".to_string());
     }
 
     cmd_args.push("--".to_string());
 
-    if let Some(submit_part) = submit_part {
+    if let Some(submit_part) = options.submit {
         cmd_args.push("--submit".to_string());
         cmd_args.push(submit_part.to_string());
     }
 
-    if time {
+    if options.time {
         cmd_args.push("--time".to_string());
     }
 
+    if options.profile {
+        cmd_args.push("--profile".to_string());
+    }
+
+    if options.watch {
+        if options.submit.is_some() || !options.synthetic.is_empty() {
+            eprintln!("`--watch` can not be combined with `--submit` or `--scale`.");
+            process::exit(1);
+        }
+        watch(puzzle, options.release, &cmd_args);
+    }
+
+    if options.synthetic.is_empty() {
+        run(&cmd_args);
+    }
+
+    for (i, synthetic) in options.synthetic.iter().enumerate() {
+        if i > 0 {
+            println!();
+        }
+        println!("{ANSI_BOLD}Scale {}{ANSI_RESET}", synthetic.scale);
+        run(&[cmd_args.clone(), synthetic.args()].concat());
+    }
+}
+
+fn run(args: &[String]) {
     let mut cmd = Command::new("cargo")
-        .args(&cmd_args)
+        .args(args)
+        .current_dir(&config::get().root)
         .stdout(Stdio::inherit())
         .stderr(Stdio::inherit())
         .spawn()
@@ -29,3 +80,197 @@ pub fn handle(day: Day, release: bool, time: bool, submit_part: Option<u8>) {
 
     cmd.wait().unwrap();
 }
+
+/* -------------------------------------------------------------------------- */
+
+/// Runs the tests of a day and then the solution on every change to the day's module,
+/// the library or the day's inputs and examples. Never returns.
+fn watch(puzzle: Puzzle, release: bool, cmd_args: &[String]) -> ! {
+    let config = config::get();
+    let dirs = [
+        config.root.join("src"),
+        config.data_dir("inputs", puzzle.year),
+        config.data_dir("examples", puzzle.year),
+    ];
+    let mut watcher = Watcher::new(&dirs).unwrap_or_else(|e| {
+        eprintln!("Failed to watch files: {e}");
+        process::exit(1);
+    });
+
+    let mut previous = None;
+    loop {
+        if let Some(answers) = run_once(puzzle, release, cmd_args) {
+            if let Some(previous) = &previous {
+                println!(
+                    "{ANSI_BOLD}Changes:{ANSI_RESET} {}",
+                    diff(previous, &answers)
+                );
+            }
+            previous = Some(answers);
+        }
+        println!("Watching for changes to {puzzle}...");
+
+        let changed = loop {
+            let changed: Vec<String> = match watcher.wait() {
+                Ok(changed) => changed,
+                Err(e) => {
+                    eprintln!("Failed to watch files: {e}");
+                    process::exit(1);
+                }
+            }
+            .into_iter()
+            .filter(|path| is_relevant(puzzle, path))
+            .map(|path| config.relative(&path))
+            .collect();
+
+            if !changed.is_empty() {
+                break changed;
+            }
+        };
+        println!("\n{ANSI_BOLD}Changed:{ANSI_RESET} {}", changed.join(", "));
+    }
+}
+
+/// Runs the tests of the day and, if they pass, the solution.
+/// Returns the answers of both parts, or [`None`] if the tests failed.
+fn run_once(puzzle: Puzzle, release: bool, cmd_args: &[String]) -> Option<[PartOutput; 2]> {
+    let bin_name = puzzle.bin_name();
+    let mut test_args = vec!["test", "--quiet", "--bin", &bin_name];
+    if release {
+        test_args.push("--release");
+    }
+
+    let tests = Command::new("cargo")
+        .args(&test_args)
+        .current_dir(&config::get().root)
+        .output();
+    match tests {
+        Ok(output) if output.status.success() => println!("Tests: ✔ passed"),
+        Ok(output) => {
+            print!("{}", String::from_utf8_lossy(&output.stdout));
+            eprint!("{}", String::from_utf8_lossy(&output.stderr));
+            println!("Tests: ✖ failed, skipped the real input");
+            return None;
+        }
+        Err(e) => {
+            eprintln!("Failed to run tests: {e}");
+            return None;
+        }
+    }
+
+    let mut cmd = Command::new("cargo")
+        .args(cmd_args)
+        .current_dir(&config::get().root)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit())
+        .spawn()
+        .ok()?;
+
+    // forward the output while collecting it to compare the answers.
+    let output: Vec<String> = BufReader::new(cmd.stdout.take()?)
+        .lines()
+        .map_while(Result::ok)
+        .inspect(|line| println!("{line}"))
+        .collect();
+    cmd.wait().ok()?;
+
+    Some([parse_part(&output, 1), parse_part(&output, 2)])
+}
+
+/// Whether a changed file can affect the solution of `puzzle`.
+fn is_relevant(puzzle: Puzzle, path: &Path) -> bool {
+    let config = config::get();
+    let src = config.root.join("src");
+
+    if path == config.bin_path(puzzle) {
+        return true;
+    }
+    if path.starts_with(src.join("bin")) {
+        return false;
+    }
+    if path.starts_with(&src) {
+        return path.extension().is_some_and(|ext| ext == "rs");
+    }
+
+    // inputs and examples of the day, e.g. `NN.txt` or `NN-1-large.txt`.
+    let day = puzzle.day.to_string();
+    path.extension().is_some_and(|ext| ext == "txt")
+        && path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .is_some_and(|stem| stem == day || stem.starts_with(&format!("{day}-")))
+}
+
+/// Summarizes how the answers changed since the previous run, e.g. `Part 1: 42 → 43 | Part 2: unchanged`.
+fn diff(previous: &[PartOutput; 2], current: &[PartOutput; 2]) -> String {
+    previous
+        .iter()
+        .zip(current)
+        .enumerate()
+        .map(|(i, (previous, current))| {
+            if previous == current {
+                format!("Part {}: unchanged", i + 1)
+            } else {
+                format!(
+                    "Part {}: {} → {}",
+                    i + 1,
+                    describe(previous),
+                    describe(current)
+                )
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" | ")
+}
+
+fn describe(output: &PartOutput) -> &str {
+    match output {
+        PartOutput::Answer(answer) => answer,
+        PartOutput::NotImplemented => "✖",
+        PartOutput::Failed => "✖ failed",
+        PartOutput::Unknown => "?",
+    }
+}
+
+#[cfg(all(test, feature = "test_lib"))]
+mod tests {
+    use super::{diff, is_relevant};
+    use crate::day;
+    use crate::template::commands::all::child_commands::PartOutput;
+    use crate::template::config;
+
+    #[test]
+    fn diffs_answers() {
+        let previous = [PartOutput::Answer("42".into()), PartOutput::NotImplemented];
+        assert_eq!(
+            diff(
+                &previous,
+                &[PartOutput::Answer("43".into()), PartOutput::NotImplemented]
+            ),
+            "Part 1: 42 → 43 | Part 2: unchanged"
+        );
+        assert_eq!(
+            diff(
+                &previous,
+                &[PartOutput::Answer("42".into()), PartOutput::Failed]
+            ),
+            "Part 1: unchanged | Part 2: ✖ → ✖ failed"
+        );
+    }
+
+    #[test]
+    fn filters_changed_files() {
+        let config = config::get();
+        let puzzle = day!(5).into();
+        let src = config.root.join("src");
+        let examples = config.data_dir("examples", None);
+
+        assert!(is_relevant(puzzle, &config.bin_path(puzzle)));
+        assert!(is_relevant(puzzle, &src.join("grid.rs")));
+        assert!(is_relevant(puzzle, &examples.join("05.txt")));
+        assert!(is_relevant(puzzle, &examples.join("05-2-large.txt")));
+        assert!(!is_relevant(puzzle, &config.bin_path(day!(6).into())));
+        assert!(!is_relevant(puzzle, &src.join(".grid.rs.swp")));
+        assert!(!is_relevant(puzzle, &examples.join("15.txt")));
+    }
+}
@@ -0,0 +1,794 @@
+/// Module that loads the project configuration from `aoc.toml` in the crate root.
+/// Every path of the template is resolved relative to the crate root, so commands also work
+/// when invoked from a subdirectory or a workspace.
+/// Only the subset of TOML in the example below is supported: tables, bare keys, and strings,
+/// integers, booleans and single-line arrays of them. Other syntax is rejected with an error.
+///
+/// ```toml
+/// year = 2023
+/// years = [2022, 2023]
+///
+/// [paths]
+/// layout = "flat"
+/// data = "data"
+/// inputs = "inputs"
+/// examples = "examples"
+/// puzzles = "puzzles"
+/// submissions = "submissions"
+/// benchmarks = "benchmarks.tsv"
+/// readme = "README.md"
+///
+/// [bench]
+/// min_samples = 10
+/// max_samples = 10000
+/// target_millis = 1000
+/// profile_secs = 5
+///
+/// [limits]
+/// day_timeout_secs = 60
+/// part_timeout_secs = 30
+/// memory = true
+///
+/// [readme]
+/// columns = ["samples", "std_dev", "delta", "bar"]
+/// sort = "slowest"
+/// chart = ".assets/benchmarks.svg"
+/// ```
+use std::{
+    collections::HashMap,
+    env,
+    fmt::Display,
+    fs, io,
+    path::{Path, PathBuf},
+    process,
+    sync::OnceLock,
+    time::Duration,
+};
+
+use crate::{Day, Puzzle, Year};
+
+static CONFIG_FILE: &str = "aoc.toml";
+
+#[derive(Debug)]
+pub enum Error {
+    Parser(String),
+    IO(io::Error),
+}
+
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Error::IO(e)
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Parser(e) => write!(f, "invalid {CONFIG_FILE}: {e}"),
+            Error::IO(e) => write!(f, "could not read {CONFIG_FILE}: {e}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BenchConfig {
+    pub min_samples: u128,
+    pub max_samples: u128,
+    /// Approximate total time spent benching one part.
+    pub target: Duration,
+    /// The time that `--profile` spends running one part.
+    pub profile: Duration,
+}
+
+impl Default for BenchConfig {
+    fn default() -> Self {
+        Self {
+            min_samples: 10,
+            max_samples: 10000,
+            target: Duration::from_secs(1),
+            profile: Duration::from_secs(5),
+        }
+    }
+}
+
+/// Limits that `cargo all` applies to every solution.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LimitsConfig {
+    /// The child process of a day is killed after this duration.
+    pub day_timeout: Option<Duration>,
+    /// A day is aborted if one of its parts runs longer than this duration.
+    pub part_timeout: Option<Duration>,
+    /// Report the peak memory of every part.
+    pub memory: bool,
+}
+
+/// An optional column of the benchmark table in the readme.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Column {
+    /// The number of benchmark samples of each part.
+    Samples,
+    /// The standard deviation of each part.
+    StdDev,
+    /// The change of the total runtime of a day since the previous run.
+    Delta,
+    /// A bar of the total runtime of a day, relative to the slowest day.
+    Bar,
+}
+
+/// The order of the rows of the benchmark table.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Sort {
+    #[default]
+    Day,
+    Slowest,
+}
+
+/// How the benchmark table in the readme is rendered.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReadmeConfig {
+    pub columns: Vec<Column>,
+    pub sort: Sort,
+    /// Where the chart of the total runtime over all recorded runs is written, relative to the crate root.
+    /// [`None`] if `readme.chart` is set to an empty string.
+    pub chart: Option<PathBuf>,
+}
+
+impl Default for ReadmeConfig {
+    fn default() -> Self {
+        Self {
+            columns: vec![],
+            sort: Sort::default(),
+            chart: Some(".assets/benchmarks.svg".into()),
+        }
+    }
+}
+
+/// How the files of the default year are laid out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Layout {
+    /// The default year uses `src/bin/NN.rs` and `data/inputs/NN.txt`, other years use the per-year layout.
+    Flat,
+    /// Every year uses `src/bin/YYYY_NN.rs` and `data/YYYY/inputs/NN.txt`.
+    PerYear,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Config {
+    /// The crate root, all relative paths are resolved against it.
+    pub root: PathBuf,
+    /// The year used when no `--year` is passed.
+    pub year: Option<Year>,
+    /// The years that `cargo all` runs, defaults to `year`.
+    pub years: Vec<Year>,
+    pub layout: Layout,
+    pub data: PathBuf,
+    pub inputs: PathBuf,
+    pub examples: PathBuf,
+    pub puzzles: PathBuf,
+    pub submissions: PathBuf,
+    /// The history of benchmark runs, relative to the data directory.
+    pub benchmarks: PathBuf,
+    pub readme: PathBuf,
+    pub bench: BenchConfig,
+    pub limits: LimitsConfig,
+    pub table: ReadmeConfig,
+}
+
+impl Config {
+    #[must_use]
+    pub fn with_root(root: &Path) -> Self {
+        Self {
+            root: root.to_path_buf(),
+            year: None,
+            years: vec![],
+            layout: Layout::Flat,
+            data: "data".into(),
+            inputs: "inputs".into(),
+            examples: "examples".into(),
+            puzzles: "puzzles".into(),
+            submissions: "submissions".into(),
+            benchmarks: "benchmarks.tsv".into(),
+            readme: "README.md".into(),
+            bench: BenchConfig::default(),
+            limits: LimitsConfig::default(),
+            table: ReadmeConfig::default(),
+        }
+    }
+
+    /// Loads `aoc.toml` from `root`. A missing file results in the default configuration.
+    pub fn load(root: &Path) -> Result<Self, Error> {
+        match fs::read_to_string(root.join(CONFIG_FILE)) {
+            Ok(s) => Self::parse(root, &s),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::with_root(root)),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn parse(root: &Path, s: &str) -> Result<Self, Error> {
+        let mut config = Self::with_root(root);
+
+        for (key, value) in parse_toml(s)? {
+            match key.as_str() {
+                "year" => config.year = Some(value.year(&key)?),
+                "years" => {
+                    config.years = value
+                        .array(&key)?
+                        .into_iter()
+                        .map(|v| v.year(&key))
+                        .collect::<Result<_, _>>()?;
+                }
+                "paths.layout" => {
+                    config.layout = match value.string(&key)?.as_str() {
+                        "flat" => Layout::Flat,
+                        "per_year" => Layout::PerYear,
+                        _ => {
+                            return Err(Error::Parser(
+                                "`paths.layout` must be \"flat\" or \"per_year\"".into(),
+                            ))
+                        }
+                    };
+                }
+                "paths.data" => config.data = value.string(&key)?.into(),
+                "paths.inputs" => config.inputs = value.string(&key)?.into(),
+                "paths.examples" => config.examples = value.string(&key)?.into(),
+                "paths.puzzles" => config.puzzles = value.string(&key)?.into(),
+                "paths.submissions" => config.submissions = value.string(&key)?.into(),
+                "paths.benchmarks" => config.benchmarks = value.string(&key)?.into(),
+                "paths.readme" => config.readme = value.string(&key)?.into(),
+                "bench.min_samples" => config.bench.min_samples = value.integer(&key)?,
+                "bench.max_samples" => config.bench.max_samples = value.integer(&key)?,
+                "bench.target_millis" => {
+                    config.bench.target = Duration::from_millis(value.integer(&key)?);
+                }
+                "bench.profile_secs" => {
+                    config.bench.profile = Duration::from_secs(value.integer(&key)?);
+                }
+                "limits.day_timeout_secs" => {
+                    config.limits.day_timeout = Some(Duration::from_secs(value.integer(&key)?));
+                }
+                "limits.part_timeout_secs" => {
+                    config.limits.part_timeout = Some(Duration::from_secs(value.integer(&key)?));
+                }
+                "limits.memory" => config.limits.memory = value.bool(&key)?,
+                "readme.columns" => {
+                    config.table.columns = value
+                        .array(&key)?
+                        .into_iter()
+                        .map(|v| match v.string(&key)?.as_str() {
+                            "samples" => Ok(Column::Samples),
+                            "std_dev" => Ok(Column::StdDev),
+                            "delta" => Ok(Column::Delta),
+                            "bar" => Ok(Column::Bar),
+                            c => Err(Error::Parser(format!("unknown column \"{c}\" in `{key}`"))),
+                        })
+                        .collect::<Result<_, _>>()?;
+                }
+                "readme.sort" => {
+                    config.table.sort = match value.string(&key)?.as_str() {
+                        "day" => Sort::Day,
+                        "slowest" => Sort::Slowest,
+                        _ => {
+                            return Err(Error::Parser(
+                                "`readme.sort` must be \"day\" or \"slowest\"".into(),
+                            ))
+                        }
+                    };
+                }
+                "readme.chart" => {
+                    let chart = value.string(&key)?;
+                    config.table.chart = (!chart.is_empty()).then(|| chart.into());
+                }
+                _ => return Err(Error::Parser(format!("unknown key `{key}`"))),
+            }
+        }
+
+        if config.bench.min_samples > config.bench.max_samples {
+            return Err(Error::Parser(
+                "`bench.min_samples` is larger than `bench.max_samples`".into(),
+            ));
+        }
+
+        Ok(config)
+    }
+
+    /// The year from `AOC_YEAR` if set, the configured year otherwise.
+    #[must_use]
+    pub fn year(&self) -> Option<Year> {
+        match env::var("AOC_YEAR") {
+            Ok(x) => x.parse().ok().or(self.year),
+            Err(_) => self.year,
+        }
+    }
+
+    /// Creates the [`Puzzle`] for a day of `year`, or of the default year if [`None`].
+    /// Puzzles of the default year have no year of their own in the flat layout.
+    #[must_use]
+    pub fn puzzle(&self, year: Option<Year>, day: Day) -> Puzzle {
+        let year = year.or_else(|| self.year());
+        match (self.layout, year) {
+            (Layout::Flat, year) if year == self.year() => Puzzle::from(day),
+            (_, Some(year)) => Puzzle::new(year, day),
+            (_, None) => Puzzle::from(day),
+        }
+    }
+
+    /// The directory of a data folder, e.g. `data/inputs` or `data/2022/inputs`.
+    #[must_use]
+    pub fn data_dir(&self, folder: &str, year: Option<Year>) -> PathBuf {
+        let mut data = self.root.join(&self.data);
+        if let Some(year) = year {
+            data = data.join(year.to_string());
+        }
+        match folder {
+            "inputs" => data.join(&self.inputs),
+            "examples" => data.join(&self.examples),
+            "puzzles" => data.join(&self.puzzles),
+            "submissions" => data.join(&self.submissions),
+            folder => data.join(folder),
+        }
+    }
+
+    #[must_use]
+    pub fn input_path(&self, puzzle: Puzzle) -> PathBuf {
+        self.data_dir("inputs", puzzle.year)
+            .join(format!("{}.txt", puzzle.day))
+    }
+
+    #[must_use]
+    pub fn example_path(&self, puzzle: Puzzle) -> PathBuf {
+        self.data_dir("examples", puzzle.year)
+            .join(format!("{}.txt", puzzle.day))
+    }
+
+    #[must_use]
+    pub fn puzzle_path(&self, puzzle: Puzzle) -> PathBuf {
+        self.data_dir("puzzles", puzzle.year)
+            .join(format!("{}.md", puzzle.day))
+    }
+
+    #[must_use]
+    pub fn submissions_path(&self, puzzle: Puzzle) -> PathBuf {
+        self.data_dir("submissions", puzzle.year)
+            .join(format!("{}.tsv", puzzle.day))
+    }
+
+    /// The folded stacks of a part written by `--profile`.
+    #[must_use]
+    pub fn profile_path(&self, puzzle: Puzzle, part: u8) -> PathBuf {
+        self.data_dir("profiles", puzzle.year)
+            .join(format!("{}-{part}.folded", puzzle.day))
+    }
+
+    #[must_use]
+    pub fn bin_path(&self, puzzle: Puzzle) -> PathBuf {
+        self.root
+            .join("src")
+            .join("bin")
+            .join(format!("{}.rs", puzzle.bin_name()))
+    }
+
+    #[must_use]
+    pub fn benchmarks_path(&self) -> PathBuf {
+        self.root.join(&self.data).join(&self.benchmarks)
+    }
+
+    #[must_use]
+    pub fn readme_path(&self) -> PathBuf {
+        self.root.join(&self.readme)
+    }
+
+    /// Formats a path relative to the crate root for display.
+    #[must_use]
+    pub fn relative(&self, path: &Path) -> String {
+        path.strip_prefix(&self.root)
+            .unwrap_or(path)
+            .display()
+            .to_string()
+    }
+}
+
+/// Returns the configuration of this crate. It is loaded once on first use,
+/// an invalid configuration is printed and exits the process.
+pub fn get() -> &'static Config {
+    static CONFIG: OnceLock<Config> = OnceLock::new();
+    CONFIG.get_or_init(|| {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+        Config::load(root).unwrap_or_else(|e| {
+            eprintln!("Error: {e}");
+            process::exit(1);
+        })
+    })
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Value {
+    String(String),
+    Integer(i64),
+    Bool(bool),
+    Array(Vec<Value>),
+}
+
+impl Value {
+    fn string(self, key: &str) -> Result<String, Error> {
+        match self {
+            Value::String(s) => Ok(s),
+            _ => Err(Error::Parser(format!("`{key}` must be a string"))),
+        }
+    }
+
+    fn integer<T: TryFrom<i64>>(self, key: &str) -> Result<T, Error> {
+        match self {
+            Value::Integer(i) => {
+                T::try_from(i).map_err(|_| Error::Parser(format!("`{key}` is out of range")))
+            }
+            _ => Err(Error::Parser(format!("`{key}` must be an integer"))),
+        }
+    }
+
+    fn bool(self, key: &str) -> Result<bool, Error> {
+        match self {
+            Value::Bool(b) => Ok(b),
+            _ => Err(Error::Parser(format!("`{key}` must be a boolean"))),
+        }
+    }
+
+    fn year(self, key: &str) -> Result<Year, Error> {
+        Year::new(self.integer(key)?)
+            .ok_or_else(|| Error::Parser(format!("`{key}` must be a year of advent")))
+    }
+
+    fn array(self, key: &str) -> Result<Vec<Value>, Error> {
+        match self {
+            Value::Array(values) => Ok(values),
+            _ => Err(Error::Parser(format!("`{key}` must be an array"))),
+        }
+    }
+}
+
+/// Parses the subset of TOML used by `aoc.toml`: `[table]` headers and `key = value` pairs with
+/// bare keys, where a value is a basic string in double quotes, a decimal integer, a boolean
+/// or an array of them on a single line.
+/// Other TOML syntax, e.g. multi-line arrays, inline tables or literal strings, is rejected
+/// with an error that names it instead of being misread.
+/// Keys are returned with their table as prefix, e.g. `paths.data`.
+fn parse_toml(s: &str) -> Result<HashMap<String, Value>, Error> {
+    let mut values = HashMap::new();
+    let mut table = String::new();
+
+    for (i, line) in s.lines().enumerate() {
+        let line = strip_comment(line).trim();
+        let err = |msg: &str| Error::Parser(format!("line {}: {msg}", i + 1));
+
+        if line.is_empty() {
+            continue;
+        }
+
+        if line.starts_with("[[") {
+            return Err(err("arrays of tables are not supported"));
+        }
+        if let Some(name) = line.strip_prefix('[') {
+            let name = name
+                .strip_suffix(']')
+                .ok_or_else(|| err("unclosed table"))?
+                .trim();
+            check_key(name).map_err(|e| err(&e))?;
+            table = format!("{name}.");
+            continue;
+        }
+
+        let (key, value) = line.split_once('=').ok_or_else(|| err("expected `=`"))?;
+        check_key(key.trim()).map_err(|e| err(&e))?;
+        let key = format!("{table}{}", key.trim());
+        let value = parse_value(value.trim()).map_err(|e| err(&e))?;
+
+        if values.insert(key.clone(), value).is_some() {
+            return Err(err(&format!("duplicate key `{key}`")));
+        }
+    }
+
+    Ok(values)
+}
+
+/// Checks that `key` consists of bare keys, optionally joined by dots.
+fn check_key(key: &str) -> Result<(), String> {
+    let bare = |part: &str| {
+        !part.is_empty()
+            && part
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    };
+    if key.split('.').map(str::trim).all(bare) {
+        Ok(())
+    } else {
+        Err(format!("invalid key `{key}`, only bare keys are supported"))
+    }
+}
+
+fn strip_comment(line: &str) -> &str {
+    let mut in_string = false;
+    let mut chars = line.char_indices();
+    while let Some((i, ch)) = chars.next() {
+        match ch {
+            '\\' if in_string => {
+                chars.next();
+            }
+            '"' => in_string = !in_string,
+            '#' if !in_string => return &line[..i],
+            _ => {}
+        }
+    }
+    line
+}
+
+/// Splits the elements of an array at the commas outside of strings.
+fn split_array(inner: &str) -> Vec<&str> {
+    let mut elements = vec![];
+    let mut start = 0;
+    let mut in_string = false;
+    let mut chars = inner.char_indices();
+    while let Some((i, ch)) = chars.next() {
+        match ch {
+            '\\' if in_string => {
+                chars.next();
+            }
+            '"' => in_string = !in_string,
+            ',' if !in_string => {
+                elements.push(&inner[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    elements.push(&inner[start..]);
+    elements
+}
+
+fn parse_value(s: &str) -> Result<Value, String> {
+    if s.starts_with('{') {
+        return Err("inline tables are not supported".into());
+    }
+    if let Some(inner) = s.strip_prefix('[') {
+        let inner = inner
+            .strip_suffix(']')
+            .ok_or("arrays have to be closed on the same line")?;
+        let mut elements: Vec<&str> = split_array(inner).into_iter().map(str::trim).collect();
+        // a trailing comma leaves an empty last element.
+        if elements.last() == Some(&"") {
+            elements.pop();
+        }
+        return elements
+            .into_iter()
+            .map(|element| match element {
+                "" => Err("empty array element".to_string()),
+                e if e.starts_with('[') => Err("nested arrays are not supported".to_string()),
+                e => parse_value(e),
+            })
+            .collect::<Result<_, _>>()
+            .map(Value::Array);
+    }
+    if s.starts_with("\"\"\"") || s.starts_with('\'') {
+        return Err("only basic strings in double quotes are supported".into());
+    }
+    if let Some(inner) = s.strip_prefix('"') {
+        return parse_string(inner).map(Value::String);
+    }
+    match s {
+        "true" => Ok(Value::Bool(true)),
+        "false" => Ok(Value::Bool(false)),
+        s => s.replace('_', "").parse().map(Value::Integer).map_err(|_| {
+            format!("unsupported value `{s}`, expected a string, integer, boolean or array")
+        }),
+    }
+}
+
+/// Unescapes a basic string, `s` is the rest of the value after the opening quote.
+fn parse_string(s: &str) -> Result<String, String> {
+    let mut out = String::new();
+    let mut chars = s.chars();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '"' if chars.as_str().is_empty() => return Ok(out),
+            '"' => return Err("unexpected characters after string".into()),
+            '\\' => {
+                let escape = chars.next().ok_or("unclosed string")?;
+                out.push(match escape {
+                    'b' => '\u{8}',
+                    't' => '\t',
+                    'n' => '\n',
+                    'f' => '\u{c}',
+                    'r' => '\r',
+                    c @ ('\\' | '"') => c,
+                    'u' | 'U' => {
+                        let len = if escape == 'u' { 4 } else { 8 };
+                        let hex: String = chars.by_ref().take(len).collect();
+                        u32::from_str_radix(&hex, 16)
+                            .ok()
+                            .filter(|_| hex.len() == len)
+                            .and_then(char::from_u32)
+                            .ok_or_else(|| format!("invalid unicode escape `\\{escape}{hex}`"))?
+                    }
+                    c => return Err(format!("unsupported escape `\\{c}`")),
+                });
+            }
+            ch => out.push(ch),
+        }
+    }
+    Err("unclosed string".into())
+}
+
+#[cfg(all(test, feature = "test_lib"))]
+mod tests {
+    use super::{parse_toml, Column, Config, Layout, Sort, Value};
+    use crate::{day, year, Puzzle};
+    use std::{path::Path, time::Duration};
+
+    #[test]
+    fn parses_toml_subset() {
+        let values = parse_toml(
+            "year = 2023 # comment\n[paths]\ndata = \"my # data\"\n\n[bench]\nmax_samples = 1_000\nflag = true\n",
+        )
+        .unwrap();
+        assert_eq!(values["year"], Value::Integer(2023));
+        assert_eq!(values["paths.data"], Value::String("my # data".into()));
+        assert_eq!(values["bench.max_samples"], Value::Integer(1000));
+        assert_eq!(values["bench.flag"], Value::Bool(true));
+
+        let values = parse_toml("a = [\"a,b\", \"c\\\",d\", 1]\n").unwrap();
+        assert_eq!(
+            values["a"],
+            Value::Array(vec![
+                Value::String("a,b".into()),
+                Value::String("c\",d".into()),
+                Value::Integer(1)
+            ])
+        );
+    }
+
+    #[test]
+    fn rejects_unsupported_toml() {
+        let error = |s: &str| parse_toml(s).unwrap_err().to_string();
+        assert_eq!(
+            error("years = [\n  2022,\n]\n"),
+            "invalid aoc.toml: line 1: arrays have to be closed on the same line"
+        );
+        assert!(error("paths = { data = \"d\" }").contains("inline tables"));
+        assert!(error("[[days]]").contains("arrays of tables"));
+        assert!(error("a = 'literal'").contains("basic strings"));
+        assert!(error("a = [[1], [2]]").contains("nested arrays"));
+        assert!(error("a = [1,, 2]").contains("empty array element"));
+        assert!(error("\"a\" = 1").contains("bare keys"));
+        assert!(error("a = \"b\" \"c\"").contains("after string"));
+        assert!(error("a = \"b\\\"").contains("unclosed string"));
+        assert!(error("a = \"\\x\"").contains("unsupported escape"));
+        assert!(error("a = 1.5").contains("unsupported value `1.5`"));
+
+        let values = parse_toml("a = [1, 2,]\nb = \"\\u00e9\\\\\"\n").unwrap();
+        assert_eq!(
+            values["a"],
+            Value::Array(vec![Value::Integer(1), Value::Integer(2)])
+        );
+        assert_eq!(values["b"], Value::String("é\\".into()));
+    }
+
+    #[test]
+    fn defaults_without_file() {
+        let config = Config::parse(Path::new("/crate"), "").unwrap();
+        assert_eq!(config, Config::with_root(Path::new("/crate")));
+        assert_eq!(
+            config.input_path(day!(1).into()),
+            Path::new("/crate/data/inputs/01.txt")
+        );
+        assert_eq!(config.readme_path(), Path::new("/crate/README.md"));
+        assert_eq!(config.table.chart, Some(".assets/benchmarks.svg".into()));
+    }
+
+    #[test]
+    fn resolves_configured_paths() {
+        let config = Config::parse(
+            Path::new("/crate"),
+            "year = 2022\n[paths]\ndata = \"aoc\"\ninputs = \"in\"\nreadme = \"docs/README.md\"\n[bench]\ntarget_millis = 200\nprofile_secs = 2",
+        )
+        .unwrap();
+        assert_eq!(config.year, Some(year!(2022)));
+        assert_eq!(
+            config.input_path(day!(3).into()),
+            Path::new("/crate/aoc/in/03.txt")
+        );
+        assert_eq!(
+            config.puzzle_path(day!(3).into()),
+            Path::new("/crate/aoc/puzzles/03.md")
+        );
+        assert_eq!(config.readme_path(), Path::new("/crate/docs/README.md"));
+        assert_eq!(config.bench.target, Duration::from_millis(200));
+        assert_eq!(config.bench.profile, Duration::from_secs(2));
+        assert_eq!(
+            config.profile_path(day!(3).into(), 2),
+            Path::new("/crate/aoc/profiles/03-2.folded")
+        );
+    }
+
+    #[test]
+    fn resolves_other_years() {
+        let config =
+            Config::parse(Path::new("/crate"), "year = 2023\nyears = [2022, 2023]\n").unwrap();
+        assert_eq!(config.years, vec![year!(2022), year!(2023)]);
+        assert_eq!(config.puzzle(None, day!(1)), Puzzle::from(day!(1)));
+        assert_eq!(
+            config.puzzle(Some(year!(2023)), day!(1)),
+            Puzzle::from(day!(1))
+        );
+
+        let puzzle = config.puzzle(Some(year!(2022)), day!(5));
+        assert_eq!(puzzle, Puzzle::new(year!(2022), day!(5)));
+        assert_eq!(
+            config.input_path(puzzle),
+            Path::new("/crate/data/2022/inputs/05.txt")
+        );
+        assert_eq!(
+            config.bin_path(puzzle),
+            Path::new("/crate/src/bin/2022_05.rs")
+        );
+    }
+
+    #[test]
+    fn uses_per_year_layout() {
+        let config = Config::parse(
+            Path::new("/crate"),
+            "year = 2023\n[paths]\nlayout = \"per_year\"\n",
+        )
+        .unwrap();
+        assert_eq!(config.layout, Layout::PerYear);
+
+        let puzzle = config.puzzle(None, day!(1));
+        assert_eq!(puzzle, Puzzle::new(year!(2023), day!(1)));
+        assert_eq!(
+            config.example_path(puzzle),
+            Path::new("/crate/data/2023/examples/01.txt")
+        );
+    }
+
+    #[test]
+    fn parses_limits() {
+        let config = Config::parse(
+            Path::new("/crate"),
+            "[limits]\nday_timeout_secs = 60\nmemory = true\n",
+        )
+        .unwrap();
+        assert_eq!(config.limits.day_timeout, Some(Duration::from_secs(60)));
+        assert_eq!(config.limits.part_timeout, None);
+        assert!(config.limits.memory);
+        assert!(Config::parse(Path::new("/"), "[limits]\nmemory = 1").is_err());
+    }
+
+    #[test]
+    fn parses_readme_table() {
+        let config = Config::parse(
+            Path::new("/crate"),
+            "[readme]\ncolumns = [\"delta\", \"bar\"]\nsort = \"slowest\"\nchart = \".assets/b.svg\"\n",
+        )
+        .unwrap();
+        assert_eq!(config.table.columns, vec![Column::Delta, Column::Bar]);
+        assert_eq!(config.table.sort, Sort::Slowest);
+        assert_eq!(config.table.chart, Some(".assets/b.svg".into()));
+        assert_eq!(
+            config.benchmarks_path(),
+            Path::new("/crate/data/benchmarks.tsv")
+        );
+        assert!(Config::parse(Path::new("/"), "[readme]\ncolumns = [\"median\"]").is_err());
+
+        let config = Config::parse(Path::new("/crate"), "[readme]\nchart = \"\"\n").unwrap();
+        assert_eq!(config.table.chart, None);
+    }
+
+    #[test]
+    fn rejects_invalid_config() {
+        assert!(Config::parse(Path::new("/"), "yaer = 2023").is_err());
+        assert!(Config::parse(Path::new("/"), "year = \"2023\"").is_err());
+        assert!(Config::parse(Path::new("/"), "year = 2023\nyear = 2024").is_err());
+        assert!(Config::parse(Path::new("/"), "[bench\n").is_err());
+        assert!(Config::parse(Path::new("/"), "years = [2014]").is_err());
+        assert!(Config::parse(Path::new("/"), "[paths]\nlayout = \"nested\"").is_err());
+    }
+}
@@ -0,0 +1,181 @@
+/// Module that discovers the example files of a day and their expected answers.
+///
+/// Besides the classic `data/examples/NN.txt`, a day can provide any number of additional examples:
+///  1. `NN-1.txt` / `NN-2.txt` only apply to part one / part two.
+///  2. `NN-<name>.txt` applies to both parts, `NN-1-<name>.txt` / `NN-2-<name>.txt` to one part.
+///
+/// Every example may start with a header that lists the expected answers:
+///
+/// ```text
+/// ---
+/// part_one: 142
+/// part_two: 281
+/// ---
+/// <example input>
+/// ```
+use std::{fs, path::Path};
+
+use crate::Day;
+
+static HEADER_DELIMITER: &str = "---";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Example {
+    /// File name of the example without the `.txt` extension, e.g. `01-2-large`.
+    pub name: String,
+    /// The part this example is restricted to, [`None`] if it applies to both parts.
+    pub part: Option<u8>,
+    /// The example input with the header removed.
+    pub input: String,
+    pub part_one: Option<String>,
+    pub part_two: Option<String>,
+}
+
+impl Example {
+    /// Parses the contents of an example file.
+    #[must_use]
+    pub fn parse(name: &str, part: Option<u8>, contents: &str) -> Self {
+        let (header, input) = split_header(contents);
+        let mut example = Self {
+            name: name.to_string(),
+            part,
+            input: input.to_string(),
+            part_one: None,
+            part_two: None,
+        };
+
+        for line in header.lines() {
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let value = Some(value.trim().to_string());
+            match key.trim() {
+                "part_one" | "part 1" | "1" => example.part_one = value,
+                "part_two" | "part 2" | "2" => example.part_two = value,
+                _ => {}
+            }
+        }
+
+        example
+    }
+
+    /// Returns the expected answer of the given part, if the header declares one.
+    #[must_use]
+    pub fn expected(&self, part: u8) -> Option<&str> {
+        match part {
+            1 => self.part_one.as_deref(),
+            2 => self.part_two.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if the example should be run for the given part.
+    #[must_use]
+    pub fn applies_to(&self, part: u8) -> bool {
+        self.part.is_none_or(|p| p == part)
+    }
+}
+
+/// Splits the optional answer header from the rest of a file.
+/// Files without a header are returned unchanged.
+#[must_use]
+pub fn split_header(contents: &str) -> (&str, &str) {
+    let Some(rest) = contents
+        .strip_prefix(HEADER_DELIMITER)
+        .and_then(|s| s.strip_prefix('\n').or_else(|| s.strip_prefix("\r\n")))
+    else {
+        return ("", contents);
+    };
+
+    let mut offset = 0;
+    for line in rest.split_inclusive('\n') {
+        if line.trim_end() == HEADER_DELIMITER {
+            return (&rest[..offset], &rest[offset + line.len()..]);
+        }
+        offset += line.len();
+    }
+
+    ("", contents)
+}
+
+/// Returns the part an example file stem is restricted to, or [`None`] if the file does not belong to `day`.
+fn classify(stem: &str, day: Day) -> Option<Option<u8>> {
+    let day = day.to_string();
+    let rest = stem.strip_prefix(&day)?;
+
+    if rest.is_empty() {
+        return Some(None);
+    }
+
+    let rest = rest.strip_prefix('-')?;
+    let (first, _) = rest.split_once('-').unwrap_or((rest, ""));
+
+    match first {
+        "1" => Some(Some(1)),
+        "2" => Some(Some(2)),
+        _ if !rest.is_empty() => Some(None),
+        _ => None,
+    }
+}
+
+/// Collects every example of `day` found in `dir`, sorted by file name.
+pub fn examples_in(dir: &Path, day: Day) -> Vec<Example> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return vec![];
+    };
+
+    let mut examples: Vec<_> = entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let path = entry.path();
+            if path.extension()? != "txt" {
+                return None;
+            }
+            let stem = path.file_stem()?.to_str()?.to_string();
+            let part = classify(&stem, day)?;
+            let contents = fs::read_to_string(&path).ok()?;
+            Some(Example::parse(&stem, part, &contents))
+        })
+        .collect();
+
+    examples.sort_by(|a, b| a.name.cmp(&b.name));
+    examples
+}
+
+#[cfg(all(test, feature = "test_lib"))]
+mod tests {
+    use super::{classify, split_header, Example};
+    use crate::day;
+
+    #[test]
+    fn splits_header() {
+        let (header, input) = split_header("---\npart_one: 142\n---\n1abc2\n");
+        assert_eq!(header, "part_one: 142\n");
+        assert_eq!(input, "1abc2\n");
+    }
+
+    #[test]
+    fn keeps_files_without_header() {
+        assert_eq!(split_header("1abc2\n"), ("", "1abc2\n"));
+        assert_eq!(split_header("---\nno end"), ("", "---\nno end"));
+    }
+
+    #[test]
+    fn parses_expected_answers() {
+        let example = Example::parse("01", None, "---\npart_one: 142\npart_two: 281\n---\nfoo");
+        assert_eq!(example.expected(1), Some("142"));
+        assert_eq!(example.expected(2), Some("281"));
+        assert_eq!(example.input, "foo");
+    }
+
+    #[test]
+    fn classifies_file_names() {
+        assert_eq!(classify("01", day!(1)), Some(None));
+        assert_eq!(classify("01-1", day!(1)), Some(Some(1)));
+        assert_eq!(classify("01-2-large", day!(1)), Some(Some(2)));
+        assert_eq!(classify("01-large", day!(1)), Some(None));
+        assert_eq!(classify("02", day!(1)), None);
+        assert_eq!(classify("011", day!(1)), None);
+        assert_eq!(classify("01-", day!(1)), None);
+    }
+}
@@ -0,0 +1,113 @@
+/// Helpers for the optional input generators of solutions, used with `--scale`.
+use std::ops::Range;
+
+/// Signature of a day's input generator: `generate(scale, seed)` returns a puzzle input
+/// whose size grows with `scale`. The same `scale` and `seed` must produce the same input.
+pub type Generator = fn(usize, u64) -> String;
+
+/// A generated input requested with `--scale <n>` and an optional `--seed <n>`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Synthetic {
+    pub scale: usize,
+    pub seed: Option<u64>,
+}
+
+impl Synthetic {
+    /// The arguments that pass this request on to a solution bin.
+    #[must_use]
+    pub fn args(&self) -> Vec<String> {
+        let mut args = vec!["--scale".to_string(), self.scale.to_string()];
+        if let Some(seed) = self.seed {
+            args.push("--seed".to_string());
+            args.push(seed.to_string());
+        }
+        args
+    }
+}
+
+/// A small deterministic pseudo random number generator (splitmix64).
+///
+/// It is not suitable for anything but generating puzzle inputs,
+/// but it keeps generators reproducible without pulling in a dependency.
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    #[must_use]
+    pub const fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `range`. Panics if the range is empty.
+    pub fn range(&mut self, range: Range<u64>) -> u64 {
+        assert!(!range.is_empty(), "cannot pick from an empty range");
+        range.start + self.next_u64() % (range.end - range.start)
+    }
+
+    /// `true` with a probability of `p`.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn chance(&mut self, p: f64) -> bool {
+        ((self.next_u64() >> 11) as f64 / (1u64 << 53) as f64) < p
+    }
+
+    /// A random element of `items`. Panics if `items` is empty.
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.range(0..items.len() as u64) as usize]
+    }
+
+    /// Shuffles `items` in place (Fisher-Yates).
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = self.range(0..i as u64 + 1) as usize;
+            items.swap(i, j);
+        }
+    }
+}
+
+#[cfg(all(test, feature = "test_lib"))]
+mod tests {
+    use super::Rng;
+
+    #[test]
+    fn is_deterministic() {
+        let mut a = Rng::new(42);
+        let mut b = Rng::new(42);
+        let mut c = Rng::new(43);
+        let a: Vec<u64> = (0..5).map(|_| a.next_u64()).collect();
+        let b: Vec<u64> = (0..5).map(|_| b.next_u64()).collect();
+        let c: Vec<u64> = (0..5).map(|_| c.next_u64()).collect();
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+    }
+
+    #[test]
+    fn stays_in_range() {
+        let mut rng = Rng::new(0);
+        for _ in 0..1000 {
+            assert!((10..20).contains(&rng.range(10..20)));
+        }
+        assert_eq!(rng.range(5..6), 5);
+        assert!(!rng.chance(0.0));
+        assert!(rng.chance(1.0));
+    }
+
+    #[test]
+    fn shuffles_all_items() {
+        let mut rng = Rng::new(7);
+        let mut items: Vec<u32> = (0..100).collect();
+        rng.shuffle(&mut items);
+        assert_ne!(items, (0..100).collect::<Vec<_>>());
+        items.sort_unstable();
+        assert_eq!(items, (0..100).collect::<Vec<_>>());
+    }
+}
@@ -1,36 +1,118 @@
-use crate::Day;
-use std::{env, fs};
+use crate::Puzzle;
+use examples::Example;
+use std::{fs, path::PathBuf};
 
-pub mod aoc_cli;
+pub mod aoc_client;
 pub mod commands;
+pub mod config;
+pub mod examples;
+pub mod generate;
+pub mod profile;
 pub mod readme_benchmarks;
 pub mod runner;
+pub mod submissions;
+pub mod watch;
 
 pub const ANSI_ITALIC: &str = "\x1b[3m";
 pub const ANSI_BOLD: &str = "\x1b[1m";
 pub const ANSI_RESET: &str = "\x1b[0m";
 
 /// Helper function that reads a text file to a string.
+/// An expected-answer header (see [`examples`]) is stripped from the contents.
 #[must_use]
-pub fn read_file(folder: &str, day: Day) -> String {
-    let cwd = env::current_dir().unwrap();
-    let filepath = cwd.join("data").join(folder).join(format!("{day}.txt"));
-    let f = fs::read_to_string(filepath);
-    f.expect("could not open input file")
+pub fn read_file(folder: &str, puzzle: impl Into<Puzzle>) -> String {
+    let puzzle = puzzle.into();
+    read_file_named(folder, puzzle, &puzzle.day.to_string())
+}
+
+/// Helper function that reads the file of one part to a string.
+/// Falls back to the file shared by both parts if `NN-<part>.txt` does not exist.
+#[must_use]
+pub fn read_file_part(folder: &str, puzzle: impl Into<Puzzle>, part: u8) -> String {
+    let puzzle = puzzle.into();
+    let name = format!("{}-{part}", puzzle.day);
+    if get_data_dir(folder, puzzle)
+        .join(format!("{name}.txt"))
+        .exists()
+    {
+        read_file_named(folder, puzzle, &name)
+    } else {
+        read_file(folder, puzzle)
+    }
+}
+
+/// Returns every example of a puzzle that applies to `part`.
+#[must_use]
+pub fn examples(puzzle: impl Into<Puzzle>, part: u8) -> Vec<Example> {
+    let puzzle = puzzle.into();
+    examples::examples_in(&get_data_dir("examples", puzzle), puzzle.day)
+        .into_iter()
+        .filter(|example| example.applies_to(part))
+        .collect()
+}
+
+fn read_file_named(folder: &str, puzzle: Puzzle, name: &str) -> String {
+    let filepath = get_data_dir(folder, puzzle).join(format!("{name}.txt"));
+    let f = fs::read_to_string(filepath).expect("could not open input file");
+    examples::split_header(&f).1.to_string()
+}
+
+fn get_data_dir(folder: &str, puzzle: Puzzle) -> PathBuf {
+    config::get().data_dir(folder, puzzle.year)
 }
 
 /// Creates the constant `DAY` and sets up the input and runner for each part.
+/// Solutions of a year other than the default year pass the year first, e.g. `solution!(2022, 1)`.
+///
+/// Optional functions of the solution are passed after the day, in this order:
+///  - `parse = parse`: a `fn parse(input: &str) -> Input` that is timed separately.
+///    The parts then take `&Input` instead of the input string.
+///  - `generator = generate`: an input generator (see [`generate::Generator`]) that is used
+///    instead of the real input when the solution is run with `--scale <n>`.
+///
+/// E.g. `solution!(1, parse = parse, generator = generate)`.
 #[macro_export]
 macro_rules! solution {
-    ($day:expr) => {
-        /// The current day.
-        const DAY: advent_of_code::Day = advent_of_code::day!($day);
-
+    (@main [$($generator:path)?]) => {
         fn main() {
             use advent_of_code::template::runner::*;
-            let input = advent_of_code::template::read_file("inputs", DAY);
+            let Some(input) = load_input(DAY, advent_of_code::solution!(@generator $($generator)?))
+            else {
+                return;
+            };
             run_part(part_one, &input, DAY, 1);
             run_part(part_two, &input, DAY, 2);
         }
     };
+    (@main [$($generator:path)?] $parse:path) => {
+        fn main() {
+            use advent_of_code::template::runner::*;
+            let Some(input) = load_input(DAY, advent_of_code::solution!(@generator $($generator)?))
+            else {
+                return;
+            };
+            let input = run_parse($parse, &input, DAY);
+            run_part(part_one, &input, DAY, 1);
+            run_part(part_two, &input, DAY, 2);
+        }
+    };
+    (@generator) => {
+        None
+    };
+    (@generator $generator:path) => {
+        Some($generator as advent_of_code::template::generate::Generator)
+    };
+    ($year:literal, $day:literal $(, parse = $parse:path)? $(, generator = $generator:path)?) => {
+        /// The current puzzle.
+        const DAY: advent_of_code::Puzzle =
+            advent_of_code::Puzzle::new(advent_of_code::year!($year), advent_of_code::day!($day));
+
+        advent_of_code::solution!(@main [$($generator)?] $($parse)?);
+    };
+    ($day:literal $(, parse = $parse:path)? $(, generator = $generator:path)?) => {
+        /// The current day.
+        const DAY: advent_of_code::Day = advent_of_code::day!($day);
+
+        advent_of_code::solution!(@main [$($generator)?] $($parse)?);
+    };
 }
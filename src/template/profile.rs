@@ -0,0 +1,340 @@
+/// Profiling of solution parts with `cargo solve <day> --profile`.
+///
+/// A part is run repeatedly for `bench.profile_secs`. Meanwhile, a sampler thread records the
+/// scopes of [`time_scope!`](crate::time_scope) that are active, which is written as a
+/// flamegraph-compatible folded-stack file, and every scope measures its exact time for a
+/// hierarchical breakdown. Every thread keeps its own scopes, only those entered on the thread
+/// that runs the part are recorded.
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, HashMap},
+    fmt::{self, Display},
+    fs, io,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        mpsc::{self, RecvTimeoutError},
+        Arc,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::template::config;
+use crate::Puzzle;
+
+/// The interval in which the sampler records the active scopes.
+const SAMPLE_INTERVAL: Duration = Duration::from_millis(1);
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+    static SCOPES: RefCell<Scopes> = RefCell::default();
+}
+
+/// The scopes of one thread. Every scope path that was entered gets an id, its index in `paths`.
+#[derive(Default)]
+struct Scopes {
+    /// The ids of the scopes that are currently entered, outermost first.
+    stack: Vec<usize>,
+    paths: Vec<Vec<&'static str>>,
+    /// The id of every path by the id of its parent (0 for none, the id plus one otherwise) and its name.
+    ids: HashMap<(usize, &'static str), usize>,
+    /// The total time and number of calls of every path.
+    totals: Vec<(Duration, u64)>,
+    /// The id plus one of the innermost entered scope, 0 if there is none. The sampler reads it
+    /// from another thread, so entering a scope never waits on a lock.
+    current: Arc<AtomicUsize>,
+}
+
+impl Scopes {
+    fn push(&mut self, name: &'static str) {
+        let parent = self.stack.last().map_or(0, |&id| id + 1);
+        let id = *self.ids.entry((parent, name)).or_insert_with(|| {
+            let mut path = parent
+                .checked_sub(1)
+                .map_or_else(Vec::new, |id| self.paths[id].clone());
+            path.push(name);
+            self.paths.push(path);
+            self.totals.push((Duration::ZERO, 0));
+            self.paths.len() - 1
+        });
+        self.stack.push(id);
+        self.current.store(id + 1, Ordering::Relaxed);
+    }
+
+    fn pop(&mut self, elapsed: Duration) {
+        // the scopes are reset when a profile starts, which may happen inside of a scope.
+        let Some(id) = self.stack.pop() else {
+            return;
+        };
+        let (time, calls) = &mut self.totals[id];
+        *time += elapsed;
+        *calls += 1;
+        let current = self.stack.last().map_or(0, |&id| id + 1);
+        self.current.store(current, Ordering::Relaxed);
+    }
+}
+
+/// Enters a named scope of the profiler until the end of the enclosing block,
+/// or around an expression if one is passed. Scopes cost a single atomic load
+/// when the solution is not profiled and take no lock when it is.
+///
+/// ```ignore
+/// let grid = advent_of_code::time_scope!("parse", parse(input));
+///
+/// advent_of_code::time_scope!("solve");
+/// ```
+#[macro_export]
+macro_rules! time_scope {
+    ($name:expr) => {
+        let _scope = $crate::template::profile::Scope::enter($name);
+    };
+    ($name:expr, $body:expr) => {{
+        let _scope = $crate::template::profile::Scope::enter($name);
+        $body
+    }};
+}
+
+/// A scope entered with [`time_scope!`](crate::time_scope), it is left when dropped.
+#[must_use]
+pub struct Scope {
+    start: Option<Instant>,
+}
+
+impl Scope {
+    pub fn enter(name: &'static str) -> Self {
+        if !ENABLED.load(Ordering::Relaxed) {
+            return Self { start: None };
+        }
+
+        SCOPES.with_borrow_mut(|scopes| scopes.push(name));
+        Self {
+            start: Some(Instant::now()),
+        }
+    }
+}
+
+impl Drop for Scope {
+    fn drop(&mut self) {
+        let Some(start) = self.start else {
+            return;
+        };
+
+        let elapsed = start.elapsed();
+        SCOPES.with_borrow_mut(|scopes| scopes.pop(elapsed));
+    }
+}
+
+/// The result of profiling one part.
+#[derive(Debug, Default)]
+pub struct Profile {
+    pub runs: u64,
+    pub elapsed: Duration,
+    /// The number of samples of every scope path.
+    pub samples: BTreeMap<Vec<&'static str>, u64>,
+    /// The total time and number of calls of every scope path.
+    pub totals: BTreeMap<Vec<&'static str>, (Duration, u64)>,
+}
+
+impl Profile {
+    /// Formats the samples as folded stacks, one `scope;scope;scope <count>` line per path.
+    #[must_use]
+    pub fn folded(&self) -> String {
+        self.samples
+            .iter()
+            .map(|(stack, count)| format!("{} {count}\n", stack.join(";")))
+            .collect()
+    }
+}
+
+/// Prints the time of every scope per run, indented by depth.
+impl Display for Profile {
+    #[allow(clippy::cast_precision_loss, clippy::cast_possible_truncation)]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let runs = self.runs.max(1);
+        let root = self
+            .totals
+            .iter()
+            .find(|(stack, _)| stack.len() == 1)
+            .map_or(Duration::ZERO, |(_, (time, _))| *time);
+
+        for (stack, (time, calls)) in &self.totals {
+            let name = format!("{}{}", "  ".repeat(stack.len() - 1), stack[stack.len() - 1]);
+            let share = if root.is_zero() {
+                0.0
+            } else {
+                time.as_secs_f64() / root.as_secs_f64() * 100.0
+            };
+            writeln!(
+                f,
+                "  {name:<24} {:>10.1?} {share:>6.1}% {:>8} calls/run",
+                *time / runs as u32,
+                calls / runs
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Runs `func` repeatedly for `duration` while sampling the active scopes.
+/// Every run is wrapped in a root scope called `name`, only scopes on the calling thread are recorded.
+pub fn profile<I: Clone, T>(
+    func: impl Fn(I) -> T,
+    input: I,
+    name: &'static str,
+    duration: Duration,
+) -> Profile {
+    let current = SCOPES.with_borrow_mut(|scopes| {
+        *scopes = Scopes::default();
+        Arc::clone(&scopes.current)
+    });
+    ENABLED.store(true, Ordering::Relaxed);
+
+    let (stop, stopped) = mpsc::channel::<()>();
+    let sampler = thread::spawn(move || {
+        let mut samples: HashMap<usize, u64> = HashMap::new();
+        while stopped.recv_timeout(SAMPLE_INTERVAL) == Err(RecvTimeoutError::Timeout) {
+            // cloning the input happens outside of the root scope.
+            match current.load(Ordering::Relaxed) {
+                0 => {}
+                id => *samples.entry(id - 1).or_default() += 1,
+            }
+        }
+        samples
+    });
+
+    let start = Instant::now();
+    let mut runs = 0;
+    while runs == 0 || start.elapsed() < duration {
+        let cloned = input.clone();
+        let _scope = Scope::enter(name);
+        func(cloned);
+        runs += 1;
+    }
+    let elapsed = start.elapsed();
+
+    ENABLED.store(false, Ordering::Relaxed);
+    drop(stop);
+    let samples = sampler.join().unwrap();
+
+    let scopes = SCOPES.take();
+    Profile {
+        runs,
+        elapsed,
+        samples: samples
+            .into_iter()
+            .map(|(id, count)| (scopes.paths[id].clone(), count))
+            .collect(),
+        totals: scopes.paths.into_iter().zip(scopes.totals).collect(),
+    }
+}
+
+/// Writes the folded stacks of a part to the `profiles` data directory and returns the path.
+pub fn write_folded(profile: &Profile, puzzle: Puzzle, part: u8) -> io::Result<PathBuf> {
+    let path = config::get().profile_path(puzzle, part);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&path, profile.folded())?;
+    Ok(path)
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(all(test, feature = "test_lib"))]
+mod tests {
+    use super::{profile, Profile};
+    use std::{collections::BTreeMap, thread, time::Duration};
+
+    #[test]
+    fn records_nested_scopes() {
+        let result = profile(
+            |n: u64| {
+                let parsed = crate::time_scope!("parse", {
+                    thread::sleep(Duration::from_millis(2));
+                    n
+                });
+                crate::time_scope!("solve");
+                for _ in 0..2 {
+                    crate::time_scope!("step", thread::sleep(Duration::from_millis(1)));
+                }
+                parsed
+            },
+            1,
+            "part_one",
+            Duration::from_millis(50),
+        );
+
+        assert!(result.runs > 1);
+        let paths: Vec<String> = result.totals.keys().map(|k| k.join(";")).collect();
+        assert_eq!(
+            paths,
+            [
+                "part_one",
+                "part_one;parse",
+                "part_one;solve",
+                "part_one;solve;step"
+            ]
+        );
+        assert_eq!(
+            result.totals[&vec!["part_one", "solve", "step"]].1,
+            result.runs * 2
+        );
+        assert!(result.samples.keys().all(|k| k[0] == "part_one"));
+        assert!(result.samples.values().sum::<u64>() > 0);
+
+        // scopes are not recorded outside of a profile.
+        crate::time_scope!("ignored");
+        assert!(super::SCOPES.with_borrow(|scopes| scopes.stack.is_empty()));
+    }
+
+    #[test]
+    fn ignores_other_threads() {
+        let result = profile(
+            |()| {
+                thread::scope(|scope| {
+                    scope.spawn(|| {
+                        crate::time_scope!("worker", thread::sleep(Duration::from_millis(2)))
+                    });
+                    crate::time_scope!("main", thread::sleep(Duration::from_millis(2)));
+                });
+            },
+            (),
+            "part_one",
+            Duration::from_millis(20),
+        );
+
+        let paths: Vec<String> = result.totals.keys().map(|k| k.join(";")).collect();
+        assert_eq!(paths, ["part_one", "part_one;main"]);
+        assert!(result.samples.keys().all(|k| k[0] == "part_one"));
+    }
+
+    #[test]
+    fn formats_folded_stacks() {
+        let profile = Profile {
+            runs: 2,
+            elapsed: Duration::from_millis(20),
+            samples: BTreeMap::from([
+                (vec!["part_one"], 2),
+                (vec!["part_one", "parse"], 5),
+                (vec!["part_one", "solve"], 13),
+            ]),
+            totals: BTreeMap::from([
+                (vec!["part_one"], (Duration::from_millis(20), 2)),
+                (vec!["part_one", "parse"], (Duration::from_millis(5), 2)),
+            ]),
+        };
+
+        assert_eq!(
+            profile.folded(),
+            "part_one 2\npart_one;parse 5\npart_one;solve 13\n"
+        );
+        let report = profile.to_string();
+        let lines: Vec<&str> = report.lines().collect();
+        assert!(lines[0].starts_with("  part_one "));
+        assert!(lines[0].contains("10.0ms  100.0%"));
+        assert!(lines[1].starts_with("    parse "));
+        assert!(lines[1].contains("2.5ms   25.0%"));
+    }
+}
@@ -1,11 +1,19 @@
 /// Module that updates the readme me with timing information.
 /// The approach taken is similar to how `aoc-readme-stars` handles this.
-use std::{fs, io};
+use std::{fmt::Display, fs, io, path::Path};
 
-use crate::Day;
+use crate::template::{
+    config::{self, Column, ReadmeConfig, Sort},
+    runner::format_memory,
+    submissions,
+};
+use crate::Puzzle;
 
 static MARKER: &str = "<!--- benchmarking table --->";
 
+/// Width of the bars in the benchmark table, in characters.
+const BAR_WIDTH: usize = 20;
+
 #[derive(Debug)]
 pub enum Error {
     Parser(String),
@@ -20,10 +28,25 @@ impl From<std::io::Error> for Error {
 
 #[derive(Clone)]
 pub struct Timings {
-    pub day: Day,
+    pub puzzle: Puzzle,
     pub part_1: Option<String>,
     pub part_2: Option<String>,
+    /// The time of the `parse` function, for solutions that declare one.
+    pub parse: Option<String>,
     pub total_nanos: f64,
+    /// The solution was aborted by a day or part timeout.
+    pub timed_out: bool,
+    /// The peak memory of the parts in bytes, if measured.
+    pub peak_memory: Option<f64>,
+    /// Benchmark details of both parts, if they were benched.
+    pub stats: [Option<PartStats>; 2],
+}
+
+/// Benchmark details of one part.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PartStats {
+    pub samples: u128,
+    pub std_dev: String,
 }
 
 pub struct TablePosition {
@@ -31,9 +54,101 @@ pub struct TablePosition {
     pos_end: usize,
 }
 
+/* -------------------------------------------------------------------------- */
+
+/// The total runtime of a day in one benchmark run.
+#[derive(Debug, Clone, PartialEq)]
+struct Record {
+    /// Seconds since the unix epoch, shared by all days of a run.
+    timestamp: u64,
+    bin: String,
+    nanos: f64,
+}
+
+/// The results of previous benchmark runs. Stored as one tab separated line per day and run.
+#[derive(Debug, Default)]
+pub struct BenchHistory {
+    records: Vec<Record>,
+}
+
+impl BenchHistory {
+    /// Loads the history from `path`. A missing file is an empty history.
+    pub fn load(path: &Path) -> Result<Self, io::Error> {
+        match fs::read_to_string(path) {
+            Ok(s) => Ok(Self::parse(&s)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), io::Error> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, self.to_string())
+    }
+
+    #[must_use]
+    pub fn parse(s: &str) -> Self {
+        let records = s
+            .lines()
+            .filter_map(|line| {
+                let mut fields = line.split('\t');
+                Some(Record {
+                    timestamp: fields.next()?.parse().ok()?,
+                    bin: fields.next()?.to_string(),
+                    nanos: fields.next()?.parse().ok()?,
+                })
+            })
+            .collect();
+        Self { records }
+    }
+
+    /// The total runtime of a day in the most recent run that contains it.
+    fn previous(&self, puzzle: Puzzle) -> Option<f64> {
+        let bin = puzzle.bin_name();
+        self.records
+            .iter()
+            .rev()
+            .find(|r| r.bin == bin)
+            .map(|r| r.nanos)
+    }
+
+    pub fn record(&mut self, timings: &[Timings], timestamp: u64) {
+        self.records.extend(timings.iter().map(|t| Record {
+            timestamp,
+            bin: t.puzzle.bin_name(),
+            nanos: t.total_nanos,
+        }));
+    }
+
+    /// The total runtime of every run in nanoseconds, oldest first.
+    fn totals(&self) -> Vec<f64> {
+        let mut totals: Vec<(u64, f64)> = vec![];
+        for record in &self.records {
+            match totals.iter_mut().find(|(t, _)| *t == record.timestamp) {
+                Some((_, total)) => *total += record.nanos,
+                None => totals.push((record.timestamp, record.nanos)),
+            }
+        }
+        totals.sort_by_key(|(t, _)| *t);
+        totals.into_iter().map(|(_, total)| total).collect()
+    }
+}
+
+impl Display for BenchHistory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.records
+            .iter()
+            .try_for_each(|r| writeln!(f, "{}\t{}\t{:.0}", r.timestamp, r.bin, r.nanos))
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
 #[must_use]
-pub fn get_path_for_bin(day: Day) -> String {
-    format!("./src/bin/{day}.rs")
+pub fn get_path_for_bin(puzzle: Puzzle) -> String {
+    format!("./src/bin/{}.rs", puzzle.bin_name())
 }
 
 fn locate_table(readme: &str) -> Result<TablePosition, Error> {
@@ -58,104 +173,498 @@ fn locate_table(readme: &str) -> Result<TablePosition, Error> {
     Ok(TablePosition { pos_start, pos_end })
 }
 
-fn construct_table(prefix: &str, timings: Vec<Timings>, total_millis: f64) -> String {
+/// Renders `value` relative to `max` as a bar of Unicode block characters.
+fn bar(value: f64, max: f64, width: usize) -> String {
+    const BLOCKS: [char; 8] = ['▏', '▎', '▍', '▌', '▋', '▊', '▉', '█'];
+
+    if max <= 0.0 || value <= 0.0 {
+        return String::new();
+    }
+
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        clippy::cast_precision_loss
+    )]
+    let eighths = ((value / max * (width * 8) as f64).round() as usize).max(1);
+    let (full, rest) = (eighths / 8, eighths % 8);
+    let mut bar = "█".repeat(full);
+    if rest > 0 {
+        bar.push(BLOCKS[rest - 1]);
+    }
+    bar
+}
+
+fn format_delta(nanos: f64, previous: Option<f64>) -> String {
+    match previous {
+        Some(previous) if previous > 0.0 => {
+            format!("{:+.1}%", (nanos - previous) / previous * 100.0)
+        }
+        _ => "-".into(),
+    }
+}
+
+fn format_stats(stats: &[Option<PartStats>; 2], f: impl Fn(&PartStats) -> String) -> String {
+    let [part_1, part_2] = stats.each_ref().map(|s| s.as_ref().map_or("-".into(), &f));
+    format!("{part_1} / {part_2}")
+}
+
+fn construct_table(
+    prefix: &str,
+    mut timings: Vec<Timings>,
+    total_millis: f64,
+    options: &ReadmeConfig,
+    history: &BenchHistory,
+) -> String {
     let header = format!("{prefix} Benchmarks");
 
-    let mut lines: Vec<String> = vec![
-        MARKER.into(),
-        header,
-        String::new(),
-        "| Day | Part 1 | Part 2 |".into(),
-        "| :---: | :---: | :---:  |".into(),
-    ];
+    let has_parse = timings.iter().any(|t| t.parse.is_some());
+    let has_memory = timings.iter().any(|t| t.peak_memory.is_some());
+    let slowest = timings.iter().map(|t| t.total_nanos).fold(0.0, f64::max);
+
+    if options.sort == Sort::Slowest {
+        timings.sort_by(|a, b| b.total_nanos.total_cmp(&a.total_nanos));
+    }
+
+    let mut columns = vec![];
+    if has_parse {
+        columns.push("Parse");
+    }
+    if has_memory {
+        columns.push("Memory");
+    }
+    columns.extend(options.columns.iter().map(|column| match column {
+        Column::Samples => "Samples",
+        Column::StdDev => "Std-dev",
+        Column::Delta => "Delta",
+        Column::Bar => "",
+    }));
+
+    let mut lines: Vec<String> = vec![MARKER.into(), header, String::new()];
+
+    lines.push(
+        columns
+            .iter()
+            .fold(String::from("| Day | Part 1 | Part 2 |"), |header, c| {
+                header + &format!(" {c} |")
+            }),
+    );
+    lines.push(String::from("| :---: | :---: | :---:  |") + &" :---: |".repeat(columns.len()));
 
     for timing in timings {
-        let path = get_path_for_bin(timing.day);
-        lines.push(format!(
-            "| [Day {}]({}) | `{}` | `{}` |",
-            timing.day.into_inner(),
+        let missing = if timing.timed_out { "timed out" } else { "-" };
+        let path = get_path_for_bin(timing.puzzle);
+        let label = match timing.puzzle.year {
+            Some(year) => format!("{year} Day {}", timing.puzzle.day.into_inner()),
+            None => format!("Day {}", timing.puzzle.day.into_inner()),
+        };
+        let mut line = format!(
+            "| [{}]({}) | `{}` | `{}` |",
+            label,
             path,
-            timing.part_1.unwrap_or_else(|| "-".into()),
-            timing.part_2.unwrap_or_else(|| "-".into())
-        ));
+            timing.part_1.clone().unwrap_or_else(|| missing.into()),
+            timing.part_2.clone().unwrap_or_else(|| missing.into())
+        );
+        if has_parse {
+            let parse = timing.parse.as_deref().unwrap_or("-");
+            line.push_str(&format!(" `{parse}` |"));
+        }
+        if has_memory {
+            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+            let memory = timing
+                .peak_memory
+                .map_or_else(|| "-".into(), |b| format_memory(b as u64));
+            line.push_str(&format!(" `{memory}` |"));
+        }
+        for column in &options.columns {
+            let cell = match column {
+                Column::Samples => format_stats(&timing.stats, |s| s.samples.to_string()),
+                Column::StdDev => format_stats(&timing.stats, |s| format!("±{}", s.std_dev)),
+                Column::Delta => format_delta(timing.total_nanos, history.previous(timing.puzzle)),
+                Column::Bar => bar(timing.total_nanos, slowest, BAR_WIDTH),
+            };
+            line.push_str(&format!(" {cell} |"));
+        }
+        lines.push(line);
     }
 
     lines.push(String::new());
     lines.push(format!("**Total: {total_millis:.2}ms**"));
+
+    if let Some(chart) = &options.chart {
+        lines.push(String::new());
+        lines.push(format!(
+            "![Total runtime of all benchmark runs](./{})",
+            chart.display()
+        ));
+    }
+
     lines.push(MARKER.into());
 
     lines.join("\n")
 }
 
-fn update_content(s: &mut String, timings: Vec<Timings>, total_millis: f64) -> Result<(), Error> {
+/* -------------------------------------------------------------------------- */
+
+/// Renders the total runtime of every run as a line chart.
+#[allow(clippy::cast_precision_loss)]
+fn chart_svg(totals_nanos: &[f64]) -> String {
+    const WIDTH: f64 = 600.0;
+    const HEIGHT: f64 = 200.0;
+    const PADDING: f64 = 40.0;
+
+    let max = totals_nanos.iter().copied().fold(0.0, f64::max).max(1.0);
+    let step = if totals_nanos.len() > 1 {
+        (WIDTH - 2.0 * PADDING) / (totals_nanos.len() - 1) as f64
+    } else {
+        0.0
+    };
+
+    let points = totals_nanos
+        .iter()
+        .enumerate()
+        .map(|(i, total)| {
+            let x = PADDING + i as f64 * step;
+            let y = HEIGHT - PADDING - total / max * (HEIGHT - 2.0 * PADDING);
+            format!("{x:.1},{y:.1}")
+        })
+        .collect::<Vec<_>>();
+
+    let circles: String = points
+        .iter()
+        .map(|p| {
+            let (x, y) = p.split_once(',').unwrap();
+            format!(r##"<circle cx="{x}" cy="{y}" r="3" fill="#dea584"/>"##)
+        })
+        .collect();
+
+    let (bottom, right) = (HEIGHT - PADDING, WIDTH - PADDING);
+    [
+        format!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{WIDTH}" height="{HEIGHT}" viewBox="0 0 {WIDTH} {HEIGHT}" font-family="sans-serif" font-size="12">"#
+        ),
+        format!(
+            r##"<path d="M{PADDING} {PADDING} V{bottom} H{right}" stroke="#888" fill="none"/>"##
+        ),
+        format!(
+            r##"<text x="{PADDING}" y="{}" fill="#888">{:.2}ms</text>"##,
+            PADDING - 8.0,
+            max / 1_000_000.0
+        ),
+        format!(
+            r##"<text x="{right}" y="{}" text-anchor="end" fill="#888">{} {}</text>"##,
+            bottom + 20.0,
+            totals_nanos.len(),
+            if totals_nanos.len() == 1 { "run" } else { "runs" }
+        ),
+        format!(
+            r##"<polyline points="{}" stroke="#dea584" stroke-width="2" fill="none"/>"##,
+            points.join(" ")
+        ),
+        circles,
+        "</svg>".into(),
+    ]
+    .join("\n")
+        + "\n"
+}
+
+fn update_content(
+    s: &mut String,
+    timings: Vec<Timings>,
+    total_millis: f64,
+    options: &ReadmeConfig,
+    history: &BenchHistory,
+) -> Result<(), Error> {
     let positions = locate_table(s)?;
-    let table = construct_table("##", timings, total_millis);
+    let table = construct_table("##", timings, total_millis, options, history);
     s.replace_range(positions.pos_start..positions.pos_end, &table);
     Ok(())
 }
 
+/// Updates the benchmark table in the readme, records the run in the benchmark history
+/// and redraws the chart if one is configured.
 pub fn update(timings: Vec<Timings>, total_millis: f64) -> Result<(), Error> {
-    let path = "README.md";
-    let mut readme = String::from_utf8_lossy(&fs::read(path)?).to_string();
-    update_content(&mut readme, timings, total_millis)?;
-    fs::write(path, &readme)?;
+    let config = config::get();
+    let path = config.readme_path();
+    let history_path = config.benchmarks_path();
+
+    let mut history = BenchHistory::load(&history_path)?;
+    let mut readme = String::from_utf8_lossy(&fs::read(&path)?).to_string();
+    update_content(
+        &mut readme,
+        timings.clone(),
+        total_millis,
+        &config.table,
+        &history,
+    )?;
+    fs::write(&path, &readme)?;
+
+    history.record(&timings, submissions::now());
+    history.save(&history_path)?;
+
+    if let Some(chart) = &config.table.chart {
+        let chart = config.root.join(chart);
+        if let Some(parent) = chart.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(chart, chart_svg(&history.totals()))?;
+    }
+
     Ok(())
 }
 
-#[cfg(feature = "test_lib")]
+#[cfg(all(test, feature = "test_lib"))]
 mod tests {
-    use super::{update_content, Timings, MARKER};
-    use crate::day;
+    use super::{bar, chart_svg, update_content, BenchHistory, PartStats, Timings, MARKER};
+    use crate::template::config::{Column, ReadmeConfig, Sort};
+    use crate::{day, year, Puzzle};
 
     fn get_mock_timings() -> Vec<Timings> {
         vec![
             Timings {
-                day: day!(1),
+                puzzle: day!(1).into(),
                 part_1: Some("10ms".into()),
                 part_2: Some("20ms".into()),
+                parse: None,
                 total_nanos: 3e+10,
+                timed_out: false,
+                peak_memory: None,
+                stats: [None, None],
             },
             Timings {
-                day: day!(2),
+                puzzle: day!(2).into(),
                 part_1: Some("30ms".into()),
                 part_2: Some("40ms".into()),
+                parse: None,
                 total_nanos: 7e+10,
+                timed_out: false,
+                peak_memory: None,
+                stats: [None, None],
             },
             Timings {
-                day: day!(4),
+                puzzle: day!(4).into(),
                 part_1: Some("40ms".into()),
                 part_2: Some("50ms".into()),
+                parse: None,
                 total_nanos: 9e+10,
+                timed_out: false,
+                peak_memory: None,
+                stats: [None, None],
             },
         ]
     }
 
+    #[test]
+    fn shows_timeouts_and_memory() {
+        let mut s = format!("{}{}", MARKER, MARKER);
+        let timings = vec![
+            Timings {
+                puzzle: day!(1).into(),
+                part_1: Some("1ms".into()),
+                part_2: None,
+                parse: None,
+                total_nanos: 1e+6,
+                timed_out: true,
+                peak_memory: Some(3.5 * 1024.0 * 1024.0),
+                stats: [None, None],
+            },
+            Timings {
+                puzzle: day!(2).into(),
+                part_1: None,
+                part_2: None,
+                parse: None,
+                total_nanos: 0.0,
+                timed_out: false,
+                peak_memory: None,
+                stats: [None, None],
+            },
+        ];
+        update_content(
+            &mut s,
+            timings,
+            1.0,
+            &ReadmeConfig::default(),
+            &BenchHistory::default(),
+        )
+        .unwrap();
+        assert!(s.contains("| Day | Part 1 | Part 2 | Memory |"));
+        assert!(s.contains("| [Day 1](./src/bin/01.rs) | `1ms` | `timed out` | `3.5 MiB` |"));
+        assert!(s.contains("| [Day 2](./src/bin/02.rs) | `-` | `-` | `-` |"));
+    }
+
+    #[test]
+    fn renders_optional_columns() {
+        let mut timings = get_mock_timings();
+        timings[0].stats = [
+            Some(PartStats {
+                samples: 100,
+                std_dev: "1.0ms".into(),
+            }),
+            None,
+        ];
+
+        let mut history = BenchHistory::default();
+        history.record(&get_mock_timings()[..1], 1);
+        history.records[0].nanos = 2e+10;
+
+        let options = ReadmeConfig {
+            columns: vec![Column::Samples, Column::StdDev, Column::Delta, Column::Bar],
+            sort: Sort::Slowest,
+            chart: Some(".assets/benchmarks.svg".into()),
+        };
+
+        let mut s = format!("{}{}", MARKER, MARKER);
+        update_content(&mut s, timings, 190.0, &options, &history).unwrap();
+        let lines: Vec<&str> = s.lines().collect();
+        assert_eq!(
+            lines[3],
+            "| Day | Part 1 | Part 2 | Samples | Std-dev | Delta |  |"
+        );
+        assert_eq!(
+            lines[4],
+            "| :---: | :---: | :---:  | :---: | :---: | :---: | :---: |"
+        );
+        assert_eq!(
+            lines[5],
+            "| [Day 4](./src/bin/04.rs) | `40ms` | `50ms` | - / - | - / - | - | ████████████████████ |"
+        );
+        assert_eq!(
+            lines[7],
+            "| [Day 1](./src/bin/01.rs) | `10ms` | `20ms` | 100 / - | ±1.0ms / - | +50.0% | ██████▋ |"
+        );
+        assert!(s.contains("![Total runtime of all benchmark runs](./.assets/benchmarks.svg)"));
+    }
+
+    #[test]
+    fn renders_bars() {
+        assert_eq!(bar(1.0, 1.0, 2), "██");
+        assert_eq!(bar(1.0, 2.0, 2), "█");
+        assert_eq!(bar(1.0, 16.0, 2), "▏");
+        assert_eq!(bar(0.0, 16.0, 2), "");
+    }
+
+    #[test]
+    fn roundtrips_history() {
+        let mut history = BenchHistory::default();
+        history.record(&get_mock_timings(), 100);
+        history.record(&get_mock_timings()[..1], 200);
+
+        let parsed = BenchHistory::parse(&history.to_string());
+        assert_eq!(parsed.records, history.records);
+        assert_eq!(parsed.totals(), vec![1.9e+11, 3e+10]);
+        assert_eq!(parsed.previous(day!(2).into()), Some(7e+10));
+        assert_eq!(parsed.previous(day!(3).into()), None);
+
+        let svg = chart_svg(&parsed.totals());
+        assert!(svg.starts_with("<svg "));
+        assert!(svg.contains("190000.00ms"));
+        assert!(svg.contains("2 runs"));
+    }
+
+    #[test]
+    fn shows_parse_times() {
+        let mut s = format!("{}{}", MARKER, MARKER);
+        let mut timings = get_mock_timings();
+        timings[1].parse = Some("5ms".into());
+        update_content(
+            &mut s,
+            timings,
+            190.0,
+            &ReadmeConfig::default(),
+            &BenchHistory::default(),
+        )
+        .unwrap();
+        assert!(s.contains("| Day | Part 1 | Part 2 | Parse |"));
+        assert!(s.contains("| :---: | :---: | :---:  | :---: |"));
+        assert!(s.contains("| [Day 1](./src/bin/01.rs) | `10ms` | `20ms` | `-` |"));
+        assert!(s.contains("| [Day 2](./src/bin/02.rs) | `30ms` | `40ms` | `5ms` |"));
+    }
+
+    #[test]
+    fn links_other_years() {
+        let mut s = format!("{}{}", MARKER, MARKER);
+        let timings = vec![Timings {
+            puzzle: Puzzle::new(year!(2022), day!(3)),
+            part_1: Some("1ms".into()),
+            part_2: None,
+            parse: None,
+            total_nanos: 1e+6,
+            timed_out: false,
+            peak_memory: None,
+            stats: [None, None],
+        }];
+        update_content(
+            &mut s,
+            timings,
+            1.0,
+            &ReadmeConfig::default(),
+            &BenchHistory::default(),
+        )
+        .unwrap();
+        assert!(s.contains("| [2022 Day 3](./src/bin/2022_03.rs) | `1ms` | `-` |"));
+    }
+
     #[test]
     #[should_panic]
     fn errors_if_marker_not_present() {
         let mut s = "# readme".to_string();
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
+        update_content(
+            &mut s,
+            get_mock_timings(),
+            190.0,
+            &ReadmeConfig::default(),
+            &BenchHistory::default(),
+        )
+        .unwrap();
     }
 
     #[test]
     #[should_panic]
     fn errors_if_too_many_markers_present() {
         let mut s = format!("{} {} {}", MARKER, MARKER, MARKER);
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
+        update_content(
+            &mut s,
+            get_mock_timings(),
+            190.0,
+            &ReadmeConfig::default(),
+            &BenchHistory::default(),
+        )
+        .unwrap();
     }
 
     #[test]
     fn updates_empty_benchmarks() {
         let mut s = format!("foo\nbar\n{}{}\nbaz", MARKER, MARKER);
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
+        update_content(
+            &mut s,
+            get_mock_timings(),
+            190.0,
+            &ReadmeConfig::default(),
+            &BenchHistory::default(),
+        )
+        .unwrap();
         assert_eq!(s.contains("## Benchmarks"), true);
     }
 
     #[test]
     fn updates_existing_benchmarks() {
         let mut s = format!("foo\nbar\n{}{}\nbaz", MARKER, MARKER);
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
+        update_content(
+            &mut s,
+            get_mock_timings(),
+            190.0,
+            &ReadmeConfig::default(),
+            &BenchHistory::default(),
+        )
+        .unwrap();
+        update_content(
+            &mut s,
+            get_mock_timings(),
+            190.0,
+            &ReadmeConfig::default(),
+            &BenchHistory::default(),
+        )
+        .unwrap();
         assert_eq!(s.matches(MARKER).collect::<Vec<&str>>().len(), 2);
         assert_eq!(s.matches("## Benchmarks").collect::<Vec<&str>>().len(), 1);
     }
@@ -163,7 +672,17 @@ mod tests {
     #[test]
     fn format_benchmarks() {
         let mut s = format!("foo\nbar\n{}\n{}\nbaz", MARKER, MARKER);
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
+        update_content(
+            &mut s,
+            get_mock_timings(),
+            190.0,
+            &ReadmeConfig {
+                chart: None,
+                ..ReadmeConfig::default()
+            },
+            &BenchHistory::default(),
+        )
+        .unwrap();
         let expected = [
             "foo",
             "bar",
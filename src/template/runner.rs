@@ -1,63 +1,367 @@
 /// Encapsulates code that interacts with solution functions.
-use crate::template::{aoc_cli, ANSI_ITALIC, ANSI_RESET};
-use crate::Day;
+use crate::template::generate::Generator;
+use crate::template::{
+    aoc_client, config, profile, read_file, submissions, ANSI_ITALIC, ANSI_RESET,
+};
+use crate::Puzzle;
+use std::cell::{Cell, RefCell};
+use std::error::Error;
 use std::fmt::Display;
 use std::io::{stdout, Write};
-use std::process::Output;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::OnceLock;
 use std::time::{Duration, Instant};
 use std::{cmp, env, process};
+use std::{fs, thread};
 
 use super::ANSI_BOLD;
 
-pub fn run_part<I: Clone, T: Display>(func: impl Fn(I) -> Option<T>, input: I, day: Day, part: u8) {
+/// The outcome of running one part of a solution.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Outcome<T> {
+    Solved(T),
+    /// The part returned [`None`].
+    NotImplemented,
+    /// The part returned an error, contains the error and its sources.
+    Failed(Vec<String>),
+    /// The part panicked, contains the panic message and location.
+    Panicked(String),
+}
+
+/// Return types that a solution part may have: `Option<T>` or `Result<T, E>`.
+pub trait PartResult {
+    type Output: Display;
+
+    fn into_outcome(self) -> Outcome<Self::Output>;
+}
+
+impl<T: Display> PartResult for Option<T> {
+    type Output = T;
+
+    fn into_outcome(self) -> Outcome<T> {
+        self.map_or(Outcome::NotImplemented, Outcome::Solved)
+    }
+}
+
+impl<T: Display, E: Into<Box<dyn Error>>> PartResult for Result<T, E> {
+    type Output = T;
+
+    fn into_outcome(self) -> Outcome<T> {
+        match self {
+            Ok(result) => Outcome::Solved(result),
+            Err(e) => Outcome::Failed(error_chain(e.into().as_ref())),
+        }
+    }
+}
+
+/// Collects the messages of an error and all of its sources.
+fn error_chain(e: &dyn Error) -> Vec<String> {
+    let mut chain = vec![e.to_string()];
+    let mut source = e.source();
+    while let Some(e) = source {
+        chain.push(e.to_string());
+        source = e.source();
+    }
+    chain
+}
+
+/// Reads the input of a puzzle, or generates one if the solution is run with `--scale <n>`.
+/// Returns [`None`] if an input should be generated but the solution has no generator.
+pub fn load_input(puzzle: impl Into<Puzzle>, generator: Option<Generator>) -> Option<String> {
+    let puzzle = puzzle.into();
+    let Some(scale) = scale() else {
+        return Some(read_file("inputs", puzzle));
+    };
+
+    if let Some(generator) = generator {
+        Some(generator(scale, seed()))
+    } else {
+        println!("✖ no input generator");
+        eprintln!(
+            "{puzzle} has no input generator, pass one to `solution!` to run it with `--scale`."
+        );
+        None
+    }
+}
+
+/// Parses the input of a solution that declares a `parse` function, timed like a part.
+/// Parsing is benched with `--time` as well. Exits the process if parsing panics.
+pub fn run_parse<T>(parse: impl Fn(&str) -> T, input: &str, puzzle: impl Into<Puzzle>) -> T {
+    let puzzle = puzzle.into();
+    print!("Parse: ");
+    let _ = stdout().flush();
+
+    let watchdog = part_timeout().map(|timeout| Watchdog::start("Parse", timeout));
+    let timer = Instant::now();
+    let parsed = catch_panic(|| parse(input));
+    let base_time = timer.elapsed();
+    drop(watchdog);
+
+    let parsed = match parsed {
+        Ok(parsed) => parsed,
+        Err(message) => {
+            print!("\r");
+            println!("Parse: ✖ panicked");
+            eprintln!("{puzzle}, parse {message}");
+            process::exit(1);
+        }
+    };
+
+    let timing = if env::args().any(|x| x == "--time") {
+        bench(&parse, input, &base_time)
+    } else {
+        Timing {
+            mean: base_time,
+            std_dev: Duration::ZERO,
+            samples: 1,
+        }
+    };
+    print!("\r");
+    println!("Parse:{}", format_duration(&timing));
+
+    parsed
+}
+
+pub fn run_part<I: Clone, R: PartResult>(
+    func: impl Fn(I) -> R,
+    input: I,
+    puzzle: impl Into<Puzzle>,
+    part: u8,
+) {
+    let puzzle = puzzle.into();
     let part_str = format!("Part {part}");
 
-    let (result, duration, samples) =
-        run_timed(func, input, |result| print_result(result, &part_str, ""));
+    if selected_part().is_some_and(|selected| selected != part) {
+        return;
+    }
+
+    let track_memory = env::args().any(|x| x == "--memory");
+    if track_memory {
+        reset_peak_memory();
+    }
+
+    let profile_input = env::args().any(|x| x == "--profile").then(|| input.clone());
+    let (result, timing) = run_timed(&func, input, &part_str, |result| {
+        print_result(result, &part_str, "");
+    });
+
+    let mut duration_str = format_duration(&timing);
+    if let Some(bytes) = peak_memory().filter(|_| track_memory) {
+        duration_str.push_str(&format!(" [peak {}]", format_memory(bytes)));
+    }
+    print_result(&result, &part_str, &duration_str);
+
+    if let Some(input) = profile_input.filter(|_| matches!(result, Outcome::Solved(_))) {
+        profile_part(&func, input, puzzle, part);
+    }
+
+    match result {
+        Outcome::Solved(result) => {
+            submit_result(result, puzzle, part);
+        }
+        Outcome::Failed(chain) => {
+            eprintln!("{puzzle}, part {part} failed: {}", chain[0]);
+            for cause in &chain[1..] {
+                eprintln!("  caused by: {cause}");
+            }
+        }
+        Outcome::Panicked(message) => eprintln!("{puzzle}, part {part} {message}"),
+        Outcome::NotImplemented => {}
+    }
+}
+
+/// Runs a part for `bench.profile_secs`, prints the time of its scopes and writes the sampled stacks.
+fn profile_part<I: Clone, T>(func: impl Fn(I) -> T, input: I, puzzle: Puzzle, part: u8) {
+    print!("Part {part} > {ANSI_ITALIC}profiling{ANSI_RESET}");
+    let _ = stdout().flush();
 
-    print_result(&result, &part_str, &format_duration(&duration, samples));
+    let name = if part == 1 { "part_one" } else { "part_two" };
+    let result = profile::profile(func, input, name, config::get().bench.profile);
+    print!("\r");
+    println!(
+        "Part {part}: profiled {} runs in {:.1?}",
+        result.runs, result.elapsed
+    );
+    print!("{result}");
 
-    if let Some(result) = result {
-        submit_result(result, day, part);
+    match profile::write_folded(&result, puzzle, part) {
+        Ok(path) => println!("  wrote stacks to {}", config::get().relative(&path)),
+        Err(e) => eprintln!("Failed to write profile: {e}"),
     }
 }
 
+/// Calls a solution part, catching a panic instead of aborting the process.
+/// The default panic message is replaced by [`Outcome::Panicked`].
+fn call_part<I, R: PartResult>(func: impl Fn(I) -> R, input: I) -> Outcome<R::Output> {
+    match catch_panic(|| func(input)) {
+        Ok(result) => result.into_outcome(),
+        Err(message) => Outcome::Panicked(message),
+    }
+}
+
+thread_local! {
+    /// Whether a panic on this thread is caught by [`catch_panic`], and the message of the last one.
+    static CATCHING: Cell<bool> = const { Cell::new(false) };
+    static PANIC: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Calls `func` and returns its panic message and location if it panics.
+///
+/// The panic hook is installed once and shared by all threads: panics of threads that are
+/// inside `catch_panic` are recorded for that thread, all others go to the previous hook.
+fn catch_panic<T>(func: impl FnOnce() -> T) -> Result<T, String> {
+    static HOOK: OnceLock<()> = OnceLock::new();
+    HOOK.get_or_init(|| {
+        let default_hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if !CATCHING.get() {
+                return default_hook(info);
+            }
+            let payload = info.payload();
+            let message = payload
+                .downcast_ref::<&str>()
+                .copied()
+                .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+                .unwrap_or("Box<dyn Any>");
+            let message = match info.location() {
+                Some(location) => format!("panicked at {location}: {message}"),
+                None => format!("panicked: {message}"),
+            };
+            PANIC.set(Some(message));
+        }));
+    });
+
+    let catching = CATCHING.replace(true);
+    let result = panic::catch_unwind(AssertUnwindSafe(func));
+    CATCHING.set(catching);
+
+    result.map_err(|_| PANIC.take().unwrap_or_else(|| "panicked".into()))
+}
+
 /// Run a solution part. The behavior differs depending on whether we are running a release or debug build:
 ///  1. in debug, the function is executed once.
-///  2. in release, the function is benched (approx. 1 second of execution time or 10 samples, whatever take longer.)
-fn run_timed<I: Clone, T>(
-    func: impl Fn(I) -> T,
+///  2. in release, the function is benched (approx. 1 second of execution time or 10 samples, whatever take longer.
+///     both values can be changed in the `[bench]` table of `aoc.toml`.)
+///  3. parts that did not produce a result are never benched.
+///
+/// If `--part-timeout <secs>` is passed, the process exits when the first execution takes longer.
+fn run_timed<I: Clone, R: PartResult>(
+    func: impl Fn(I) -> R,
     input: I,
-    hook: impl Fn(&T),
-) -> (T, Duration, u128) {
+    part: &str,
+    hook: impl Fn(&Outcome<R::Output>),
+) -> (Outcome<R::Output>, Timing) {
+    let watchdog = part_timeout().map(|timeout| Watchdog::start(part, timeout));
     let timer = Instant::now();
-    let result = func(input.clone());
+    let result = call_part(&func, input.clone());
     let base_time = timer.elapsed();
+    drop(watchdog);
 
     hook(&result);
 
-    let run = if std::env::args().any(|x| x == "--time") {
+    let is_solved = matches!(result, Outcome::Solved(_));
+    let timing = if is_solved && std::env::args().any(|x| x == "--time") {
         bench(func, input, &base_time)
     } else {
-        (base_time, 1)
+        Timing {
+            mean: base_time,
+            std_dev: Duration::ZERO,
+            samples: 1,
+        }
     };
 
-    (result, run.0, run.1)
+    (result, timing)
+}
+
+/// The execution time of a part.
+struct Timing {
+    mean: Duration,
+    std_dev: Duration,
+    samples: u128,
+}
+
+/// Exits the process if a part does not finish in time. Dropping the watchdog disarms it.
+struct Watchdog {
+    _disarm: mpsc::Sender<()>,
+}
+
+impl Watchdog {
+    fn start(part: &str, timeout: Duration) -> Self {
+        let (sender, receiver) = mpsc::channel::<()>();
+        let part = part.to_string();
+        thread::spawn(move || {
+            if receiver.recv_timeout(timeout) == Err(RecvTimeoutError::Timeout) {
+                print!("\r");
+                println!("{part}: ✖ timed out after {timeout:.1?}");
+                process::exit(124);
+            }
+        });
+        Self { _disarm: sender }
+    }
+}
+
+/// The part passed with `--part <part>`, other parts are skipped.
+fn selected_part() -> Option<u8> {
+    let args: Vec<String> = env::args().collect();
+    let index = args.iter().position(|x| x == "--part")?;
+    args.get(index + 1)?.parse().ok()
+}
+
+/// The scale passed with `--scale <n>`, solutions run on a generated input if it is set.
+fn scale() -> Option<usize> {
+    let args: Vec<String> = env::args().collect();
+    let index = args.iter().position(|x| x == "--scale")?;
+    args.get(index + 1)?.parse().ok()
+}
+
+/// The seed passed with `--seed <n>` for input generators, `0` by default.
+fn seed() -> u64 {
+    let args: Vec<String> = env::args().collect();
+    args.iter()
+        .position(|x| x == "--seed")
+        .and_then(|index| args.get(index + 1)?.parse().ok())
+        .unwrap_or(0)
+}
+
+fn part_timeout() -> Option<Duration> {
+    let args: Vec<String> = env::args().collect();
+    let index = args.iter().position(|x| x == "--part-timeout")?;
+    args.get(index + 1)?.parse().ok().map(Duration::from_secs)
 }
 
-fn bench<I: Clone, T>(func: impl Fn(I) -> T, input: I, base_time: &Duration) -> (Duration, u128) {
+/// Resets the peak resident set size of the process. Only supported on Linux.
+fn reset_peak_memory() {
+    let _ = fs::write("/proc/self/clear_refs", "5");
+}
+
+/// The peak resident set size of the process in bytes, read from `/proc/self/status`.
+fn peak_memory() -> Option<u64> {
+    let status = fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|l| l.starts_with("VmHWM:"))?;
+    let kib: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kib * 1024)
+}
+
+#[must_use]
+#[allow(clippy::cast_precision_loss)]
+pub fn format_memory(bytes: u64) -> String {
+    match bytes {
+        b if b >= 1 << 30 => format!("{:.1} GiB", b as f64 / f64::from(1 << 30)),
+        b if b >= 1 << 20 => format!("{:.1} MiB", b as f64 / f64::from(1 << 20)),
+        b => format!("{:.1} KiB", b as f64 / 1024.0),
+    }
+}
+
+fn bench<I: Clone, T>(func: impl Fn(I) -> T, input: I, base_time: &Duration) -> Timing {
     let mut stdout = stdout();
 
     print!(" > {ANSI_ITALIC}benching{ANSI_RESET}");
     let _ = stdout.flush();
 
-    let bench_iterations = cmp::min(
-        10000,
-        cmp::max(
-            Duration::from_secs(1).as_nanos() / cmp::max(base_time.as_nanos(), 10),
-            10,
-        ),
-    );
+    let settings = &config::get().bench;
+    let bench_iterations = (settings.target.as_nanos() / cmp::max(base_time.as_nanos(), 10))
+        .clamp(settings.min_samples, settings.max_samples);
 
     let mut timers: Vec<Duration> = vec![];
 
@@ -69,11 +373,14 @@ fn bench<I: Clone, T>(func: impl Fn(I) -> T, input: I, base_time: &Duration) ->
         timers.push(timer.elapsed());
     }
 
-    (
-        #[allow(clippy::cast_possible_truncation)]
-        Duration::from_nanos(average_duration(&timers) as u64),
-        bench_iterations,
-    )
+    let mean = average_duration(&timers);
+
+    #[allow(clippy::cast_possible_truncation)]
+    Timing {
+        mean: Duration::from_nanos(mean as u64),
+        std_dev: Duration::from_nanos(std_deviation(&timers, mean) as u64),
+        samples: bench_iterations,
+    }
 }
 
 fn average_duration(numbers: &[Duration]) -> u128 {
@@ -84,19 +391,34 @@ fn average_duration(numbers: &[Duration]) -> u128 {
         / numbers.len() as u128
 }
 
-fn format_duration(duration: &Duration, samples: u128) -> String {
-    if samples == 1 {
-        format!(" ({duration:.1?})")
+#[allow(clippy::cast_precision_loss)]
+fn std_deviation(numbers: &[Duration], mean: u128) -> f64 {
+    let variance = numbers
+        .iter()
+        .map(|d| (d.as_nanos() as f64 - mean as f64).powi(2))
+        .sum::<f64>()
+        / numbers.len() as f64;
+    variance.sqrt()
+}
+
+fn format_duration(timing: &Timing) -> String {
+    let Timing {
+        mean,
+        std_dev,
+        samples,
+    } = timing;
+    if *samples == 1 {
+        format!(" ({mean:.1?})")
     } else {
-        format!(" ({duration:.1?} @ {samples} samples)")
+        format!(" ({mean:.1?} ± {std_dev:.1?} @ {samples} samples)")
     }
 }
 
-fn print_result<T: Display>(result: &Option<T>, part: &str, duration_str: &str) {
+fn print_result<T: Display>(result: &Outcome<T>, part: &str, duration_str: &str) {
     let is_intermediate_result = duration_str.is_empty();
 
     match result {
-        Some(result) => {
+        Outcome::Solved(result) => {
             if result.to_string().contains('\n') {
                 let str = format!("{part}: ▼ {duration_str}");
                 if is_intermediate_result {
@@ -116,7 +438,7 @@ fn print_result<T: Display>(result: &Option<T>, part: &str, duration_str: &str)
                 }
             }
         }
-        None => {
+        Outcome::NotImplemented => {
             if is_intermediate_result {
                 print!("{part}: ✖");
             } else {
@@ -124,23 +446,42 @@ fn print_result<T: Display>(result: &Option<T>, part: &str, duration_str: &str)
                 println!("{part}: ✖             ");
             }
         }
+        Outcome::Failed(_) | Outcome::Panicked(_) => {
+            let label = if matches!(result, Outcome::Failed(_)) {
+                "failed"
+            } else {
+                "panicked"
+            };
+            if is_intermediate_result {
+                print!("{part}: ✖ {label}");
+            } else {
+                print!("\r");
+                println!("{part}: ✖ {label}{duration_str}");
+            }
+        }
     }
 }
 
 /// Parse the arguments passed to `solve` and try to submit one part of the solution if:
 ///  1. we are in `--release` mode.
-///  2. aoc-cli is installed.
+///  2. a session cookie is configured.
+///  3. the local submission history does not rule the answer out.
 fn submit_result<T: Display>(
     result: T,
-    day: Day,
+    puzzle: Puzzle,
     part: u8,
-) -> Option<Result<Output, aoc_cli::AocCommandError>> {
+) -> Option<Result<aoc_client::SubmissionResponse, aoc_client::AocClientError>> {
     let args: Vec<String> = env::args().collect();
 
     if !args.contains(&"--submit".into()) {
         return None;
     }
 
+    if scale().is_some() {
+        eprintln!("Answers of generated inputs are never submitted.");
+        return None;
+    }
+
     if args.len() < 3 {
         eprintln!("Unexpected command-line input. Format: cargo solve 1 --submit 1");
         process::exit(1);
@@ -157,11 +498,113 @@ fn submit_result<T: Display>(
         return None;
     }
 
-    if aoc_cli::check().is_err() {
-        eprintln!("command \"aoc\" not found or not callable. Try running \"cargo install aoc-cli\" to install it.");
+    if aoc_client::get_session().is_none() {
+        eprintln!("{}", aoc_client::AocClientError::MissingSession);
         process::exit(1);
     }
 
-    println!("Submitting result via aoc-cli...");
-    Some(aoc_cli::submit(day, part, &result.to_string()))
+    let answer = result.to_string();
+    let mut history = match submissions::History::load(puzzle) {
+        Ok(history) => history,
+        Err(e) => {
+            eprintln!("Failed to read submission history: {e}");
+            process::exit(1);
+        }
+    };
+
+    if let Err(rejection) = history.check(part, &answer, submissions::now()) {
+        eprintln!("Refusing to submit: {rejection}");
+        return None;
+    }
+
+    println!("Submitting result...");
+    let response = aoc_client::submit(puzzle, part, &answer);
+    match &response {
+        Ok(response) => {
+            println!("{response}");
+            history.record(part, &answer, response, submissions::now());
+            if let Err(e) = history.save(puzzle) {
+                eprintln!("Failed to write submission history: {e}");
+            }
+        }
+        Err(e) => eprintln!("Failed to submit result: {e}"),
+    }
+    Some(response)
+}
+
+#[cfg(all(test, feature = "test_lib"))]
+mod tests {
+    use super::{call_part, Outcome, PartResult};
+    use std::{fmt::Display, io, num::ParseIntError};
+
+    #[derive(Debug)]
+    struct InputError(io::Error);
+
+    impl Display for InputError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "could not read input")
+        }
+    }
+
+    impl std::error::Error for InputError {
+        fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+            Some(&self.0)
+        }
+    }
+
+    #[test]
+    fn converts_options() {
+        assert_eq!(Some(1).into_outcome(), Outcome::Solved(1));
+        assert_eq!(None::<u32>.into_outcome(), Outcome::NotImplemented);
+    }
+
+    #[test]
+    fn collects_error_chain() {
+        let result: Result<u32, InputError> = Err(InputError(io::Error::other("empty")));
+        assert_eq!(
+            result.into_outcome(),
+            Outcome::Failed(vec!["could not read input".into(), "empty".into()])
+        );
+
+        let result: Result<u32, ParseIntError> = "x".parse();
+        assert_eq!(
+            result.into_outcome(),
+            Outcome::Failed(vec!["invalid digit found in string".into()])
+        );
+    }
+
+    #[test]
+    fn catches_panics() {
+        let outcome = call_part(
+            |input: &str| -> Option<u32> { panic!("bad input {input}") },
+            "1",
+        );
+        let Outcome::Panicked(message) = outcome else {
+            panic!("expected a panic, got {outcome:?}");
+        };
+        assert!(message.starts_with("panicked at src/template/runner.rs:"));
+        assert!(message.ends_with(": bad input 1"));
+
+        assert_eq!(
+            call_part(|input: &str| input.parse::<u32>().ok(), "1"),
+            Outcome::Solved(1)
+        );
+    }
+
+    #[test]
+    fn catches_panics_on_parallel_threads() {
+        let threads: Vec<_> = (0..8)
+            .map(|i| {
+                std::thread::spawn(move || {
+                    call_part(|i: u32| -> Option<u32> { panic!("thread {i}") }, i)
+                })
+            })
+            .collect();
+        for (i, thread) in threads.into_iter().enumerate() {
+            let Outcome::Panicked(message) = thread.join().unwrap() else {
+                panic!("expected a panic");
+            };
+            assert!(message.ends_with(&format!(": thread {i}")));
+        }
+    }
 }
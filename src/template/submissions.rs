@@ -0,0 +1,330 @@
+/// Module that keeps a local history of submitted answers.
+/// It refuses submissions that are known to be wrong before they reach the website,
+/// which avoids the growing lockout after every wrong answer.
+use std::{
+    fmt::Display,
+    fs, io,
+    path::PathBuf,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use crate::template::aoc_client::{SubmissionResponse, Verdict};
+use crate::template::config;
+use crate::Puzzle;
+
+/// A single submitted answer. Stored as one tab separated line per submission,
+/// tabs, line breaks and backslashes in the answer are escaped.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Submission {
+    pub part: u8,
+    /// Seconds since the unix epoch.
+    pub timestamp: u64,
+    pub verdict: Verdict,
+    /// Seconds to wait before the next submission, `0` if the website did not ask for it.
+    pub wait: u64,
+    pub answer: String,
+}
+
+impl Submission {
+    fn parse(line: &str) -> Option<Self> {
+        let mut fields = line.splitn(5, '\t');
+        Some(Self {
+            part: fields.next()?.parse().ok()?,
+            timestamp: fields.next()?.parse().ok()?,
+            verdict: parse_verdict(fields.next()?)?,
+            wait: fields.next()?.parse().ok()?,
+            answer: unescape(fields.next()?)?,
+        })
+    }
+}
+
+impl Display for Submission {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}\t{}\t{:?}\t{}\t{}",
+            self.part,
+            self.timestamp,
+            self.verdict,
+            self.wait,
+            escape(&self.answer)
+        )
+    }
+}
+
+fn escape(answer: &str) -> String {
+    let mut escaped = String::with_capacity(answer.len());
+    for c in answer.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '\t' => escaped.push_str("\\t"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn unescape(field: &str) -> Option<String> {
+    let mut answer = String::with_capacity(field.len());
+    let mut chars = field.chars();
+    while let Some(c) = chars.next() {
+        answer.push(match c {
+            '\\' => match chars.next()? {
+                '\\' => '\\',
+                't' => '\t',
+                'n' => '\n',
+                'r' => '\r',
+                _ => return None,
+            },
+            c => c,
+        });
+    }
+    Some(answer)
+}
+
+fn parse_verdict(s: &str) -> Option<Verdict> {
+    match s {
+        "Correct" => Some(Verdict::Correct),
+        "Incorrect" => Some(Verdict::Incorrect),
+        "TooHigh" => Some(Verdict::TooHigh),
+        "TooLow" => Some(Verdict::TooLow),
+        "RateLimited" => Some(Verdict::RateLimited),
+        "WrongLevel" => Some(Verdict::WrongLevel),
+        "Unknown" => Some(Verdict::Unknown),
+        _ => None,
+    }
+}
+
+/// The reason a submission was refused locally.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Rejection {
+    AlreadySolved(String),
+    AlreadyWrong,
+    TooHigh(String),
+    TooLow(String),
+    Cooldown(Duration),
+}
+
+impl Display for Rejection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Rejection::AlreadySolved(answer) => {
+                write!(f, "this part was already solved with \"{answer}\".")
+            }
+            Rejection::AlreadyWrong => write!(f, "this answer was already submitted and is wrong."),
+            Rejection::TooHigh(bound) => {
+                write!(
+                    f,
+                    "the answer is too high, \"{bound}\" was already too high."
+                )
+            }
+            Rejection::TooLow(bound) => {
+                write!(f, "the answer is too low, \"{bound}\" was already too low.")
+            }
+            Rejection::Cooldown(left) => write!(
+                f,
+                "the last submission was too recent, {}s left to wait.",
+                left.as_secs()
+            ),
+        }
+    }
+}
+
+/// All submissions of one puzzle.
+#[derive(Debug, Default)]
+pub struct History {
+    pub submissions: Vec<Submission>,
+}
+
+impl History {
+    /// Loads the history of a puzzle. A missing file is an empty history.
+    pub fn load(puzzle: Puzzle) -> Result<Self, io::Error> {
+        match fs::read_to_string(get_history_path(puzzle)) {
+            Ok(s) => Ok(Self::parse(&s)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e),
+        }
+    }
+
+    pub fn save(&self, puzzle: Puzzle) -> Result<(), io::Error> {
+        let path = get_history_path(puzzle);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, self.to_string())
+    }
+
+    #[must_use]
+    pub fn parse(s: &str) -> Self {
+        Self {
+            submissions: s.lines().filter_map(Submission::parse).collect(),
+        }
+    }
+
+    /// Checks whether `answer` may be submitted for `part` at time `now` (seconds since the unix epoch).
+    pub fn check(&self, part: u8, answer: &str, now: u64) -> Result<(), Rejection> {
+        // the cooldown applies to every part of every day, but we only know about this day.
+        if let Some(last) = self.submissions.iter().max_by_key(|s| s.timestamp) {
+            let until = last.timestamp + last.wait;
+            if now < until {
+                return Err(Rejection::Cooldown(Duration::from_secs(until - now)));
+            }
+        }
+
+        let submissions = self.submissions.iter().filter(|s| s.part == part);
+        let value = answer.parse::<i128>().ok();
+
+        for s in submissions {
+            match s.verdict {
+                Verdict::Correct => return Err(Rejection::AlreadySolved(s.answer.clone())),
+                Verdict::Incorrect | Verdict::TooHigh | Verdict::TooLow if s.answer == answer => {
+                    return Err(Rejection::AlreadyWrong)
+                }
+                Verdict::TooHigh if is_at_least(value, &s.answer) => {
+                    return Err(Rejection::TooHigh(s.answer.clone()))
+                }
+                Verdict::TooLow if is_at_most(value, &s.answer) => {
+                    return Err(Rejection::TooLow(s.answer.clone()))
+                }
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The answer that was accepted for `part`, if any.
+    #[must_use]
+    pub fn correct_answer(&self, part: u8) -> Option<&str> {
+        self.submissions
+            .iter()
+            .find(|s| s.part == part && s.verdict == Verdict::Correct)
+            .map(|s| s.answer.as_str())
+    }
+
+    pub fn record(&mut self, part: u8, answer: &str, response: &SubmissionResponse, now: u64) {
+        self.submissions.push(Submission {
+            part,
+            timestamp: now,
+            verdict: response.verdict,
+            wait: response.wait.map_or(0, |w| w.as_secs()),
+            answer: answer.to_string(),
+        });
+    }
+}
+
+impl Display for History {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.submissions.iter().try_for_each(|s| writeln!(f, "{s}"))
+    }
+}
+
+fn is_at_least(value: Option<i128>, bound: &str) -> bool {
+    matches!((value, bound.parse::<i128>()), (Some(v), Ok(b)) if v >= b)
+}
+
+fn is_at_most(value: Option<i128>, bound: &str) -> bool {
+    matches!((value, bound.parse::<i128>()), (Some(v), Ok(b)) if v <= b)
+}
+
+#[must_use]
+pub fn get_history_path(puzzle: Puzzle) -> PathBuf {
+    config::get().submissions_path(puzzle)
+}
+
+/// Seconds since the unix epoch.
+#[must_use]
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+#[cfg(all(test, feature = "test_lib"))]
+mod tests {
+    use super::{History, Rejection};
+    use crate::template::aoc_client::{SubmissionResponse, Verdict};
+    use std::time::Duration;
+
+    fn response(verdict: Verdict, wait: u64) -> SubmissionResponse {
+        SubmissionResponse {
+            verdict,
+            wait: Some(Duration::from_secs(wait)),
+            message: String::new(),
+        }
+    }
+
+    #[test]
+    fn roundtrips_history() {
+        let mut history = History::default();
+        history.record(1, "42", &response(Verdict::TooLow, 60), 1000);
+        history.record(2, "a b", &response(Verdict::Correct, 0), 2000);
+
+        let parsed = History::parse(&history.to_string());
+        assert_eq!(parsed.submissions, history.submissions);
+    }
+
+    #[test]
+    fn roundtrips_answers_with_separators() {
+        let mut history = History::default();
+        history.record(1, "a\tb\nc\\n\r", &response(Verdict::Incorrect, 0), 1000);
+        history.record(2, "7", &response(Verdict::Correct, 0), 2000);
+
+        let file = history.to_string();
+        assert_eq!(file.lines().count(), 2);
+        let parsed = History::parse(&file);
+        assert_eq!(parsed.submissions, history.submissions);
+        assert_eq!(
+            parsed.check(1, "a\tb\nc\\n\r", 3000),
+            Err(Rejection::AlreadyWrong)
+        );
+    }
+
+    #[test]
+    fn refuses_known_wrong_answers() {
+        let mut history = History::default();
+        history.record(1, "100", &response(Verdict::TooHigh, 60), 0);
+        history.record(1, "10", &response(Verdict::TooLow, 60), 100);
+        history.record(1, "abc", &response(Verdict::Incorrect, 60), 200);
+
+        assert_eq!(history.check(1, "abc", 1000), Err(Rejection::AlreadyWrong));
+        assert_eq!(history.check(1, "100", 1000), Err(Rejection::AlreadyWrong));
+        assert_eq!(
+            history.check(1, "150", 1000),
+            Err(Rejection::TooHigh("100".into()))
+        );
+        assert_eq!(
+            history.check(1, "5", 1000),
+            Err(Rejection::TooLow("10".into()))
+        );
+        assert_eq!(history.check(1, "50", 1000), Ok(()));
+        assert_eq!(history.check(2, "150", 1000), Ok(()));
+    }
+
+    #[test]
+    fn enforces_cooldown() {
+        let mut history = History::default();
+        history.record(1, "100", &response(Verdict::Incorrect, 60), 1000);
+
+        assert_eq!(
+            history.check(1, "50", 1020),
+            Err(Rejection::Cooldown(Duration::from_secs(40)))
+        );
+        assert_eq!(history.check(1, "50", 1060), Ok(()));
+    }
+
+    #[test]
+    fn refuses_solved_parts() {
+        let mut history = History::default();
+        history.record(1, "7", &response(Verdict::Correct, 0), 0);
+
+        assert_eq!(
+            history.check(1, "8", 10),
+            Err(Rejection::AlreadySolved("7".into()))
+        );
+        assert_eq!(history.correct_answer(1), Some("7"));
+        assert_eq!(history.correct_answer(2), None);
+    }
+}
@@ -0,0 +1,278 @@
+/// Module that waits for file changes in a set of directories, used by `cargo solve --watch`.
+/// Uses inotify on Linux and falls back to polling modification times elsewhere.
+use std::{
+    io,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+/// Changes that arrive within this duration after the first one are reported together,
+/// editors often write a file in several steps.
+const DEBOUNCE: Duration = Duration::from_millis(100);
+
+/// Watches directories and all of their subdirectories for changed files.
+pub struct Watcher {
+    #[cfg(target_os = "linux")]
+    inner: inotify::Inotify,
+    #[cfg(not(target_os = "linux"))]
+    inner: polling::Polling,
+}
+
+impl Watcher {
+    /// Starts watching `dirs`. Directories that do not exist are ignored.
+    pub fn new(dirs: &[PathBuf]) -> io::Result<Self> {
+        #[cfg(target_os = "linux")]
+        let mut inner = inotify::Inotify::new()?;
+        #[cfg(not(target_os = "linux"))]
+        let mut inner = polling::Polling::default();
+
+        for dir in dirs.iter().filter(|dir| dir.is_dir()) {
+            for dir in subdirectories(dir) {
+                inner.add(&dir)?;
+            }
+        }
+
+        Ok(Self { inner })
+    }
+
+    /// Blocks until at least one file changed and returns the changed paths, deduplicated.
+    pub fn wait(&mut self) -> io::Result<Vec<PathBuf>> {
+        let mut changed = self.inner.wait(None)?;
+        loop {
+            let more = self.inner.wait(Some(DEBOUNCE))?;
+            if more.is_empty() {
+                break;
+            }
+            changed.extend(more);
+        }
+
+        changed.sort();
+        changed.dedup();
+        Ok(changed)
+    }
+}
+
+/// `dir` and all directories below it.
+fn subdirectories(dir: &Path) -> Vec<PathBuf> {
+    let mut dirs = vec![dir.to_path_buf()];
+    let mut i = 0;
+    while i < dirs.len() {
+        if let Ok(entries) = std::fs::read_dir(&dirs[i]) {
+            dirs.extend(
+                entries
+                    .filter_map(Result::ok)
+                    .map(|entry| entry.path())
+                    .filter(|path| path.is_dir()),
+            );
+        }
+        i += 1;
+    }
+    dirs
+}
+
+#[cfg(target_os = "linux")]
+mod inotify {
+    use std::{
+        collections::HashMap,
+        ffi::{CString, OsStr},
+        io,
+        os::unix::ffi::OsStrExt,
+        path::{Path, PathBuf},
+        time::Duration,
+    };
+
+    const MASK: u32 = libc::IN_CLOSE_WRITE
+        | libc::IN_MOVED_TO
+        | libc::IN_CREATE
+        | libc::IN_DELETE
+        | libc::IN_MOVED_FROM;
+
+    /// The size of `struct inotify_event` without its name.
+    const EVENT_SIZE: usize = std::mem::size_of::<libc::inotify_event>();
+
+    pub struct Inotify {
+        fd: libc::c_int,
+        /// The watched directory of every watch descriptor.
+        dirs: HashMap<libc::c_int, PathBuf>,
+    }
+
+    impl Inotify {
+        pub fn new() -> io::Result<Self> {
+            // SAFETY: `inotify_init1` has no preconditions, errors are reported as `-1`.
+            let fd = unsafe { libc::inotify_init1(libc::IN_CLOEXEC | libc::IN_NONBLOCK) };
+            if fd < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(Self {
+                fd,
+                dirs: HashMap::new(),
+            })
+        }
+
+        pub fn add(&mut self, dir: &Path) -> io::Result<()> {
+            let path = CString::new(dir.as_os_str().as_bytes())?;
+            // SAFETY: `path` is a valid nul-terminated string that outlives the call.
+            let wd = unsafe { libc::inotify_add_watch(self.fd, path.as_ptr(), MASK) };
+            if wd < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            self.dirs.insert(wd, dir.to_path_buf());
+            Ok(())
+        }
+
+        /// Waits up to `timeout` (forever if [`None`]) for events and returns the changed paths.
+        pub fn wait(&mut self, timeout: Option<Duration>) -> io::Result<Vec<PathBuf>> {
+            let mut poll = libc::pollfd {
+                fd: self.fd,
+                events: libc::POLLIN,
+                revents: 0,
+            };
+            let timeout = timeout.map_or(-1, |t| {
+                libc::c_int::try_from(t.as_millis()).unwrap_or(libc::c_int::MAX)
+            });
+            // SAFETY: `poll` points to exactly one valid `pollfd`.
+            let ready = unsafe { libc::poll(&mut poll, 1, timeout) };
+            if ready < 0 {
+                let error = io::Error::last_os_error();
+                return match error.kind() {
+                    io::ErrorKind::Interrupted => Ok(vec![]),
+                    _ => Err(error),
+                };
+            }
+            if ready == 0 {
+                return Ok(vec![]);
+            }
+
+            let mut buffer = [0u8; 4096];
+            // SAFETY: `buffer` is valid for writes of `buffer.len()` bytes.
+            let read = unsafe { libc::read(self.fd, buffer.as_mut_ptr().cast(), buffer.len()) };
+            if read < 0 {
+                let error = io::Error::last_os_error();
+                return match error.kind() {
+                    io::ErrorKind::WouldBlock | io::ErrorKind::Interrupted => Ok(vec![]),
+                    _ => Err(error),
+                };
+            }
+
+            let mut changed = vec![];
+            let mut new_dirs = vec![];
+            let mut offset = 0;
+            let read = read.unsigned_abs();
+            while offset + EVENT_SIZE <= read {
+                // SAFETY: the kernel writes whole events, the header lies within the read bytes.
+                // `read_unaligned` is used because the buffer has no alignment guarantees.
+                let event: libc::inotify_event = unsafe {
+                    std::ptr::read_unaligned(
+                        buffer[offset..].as_ptr().cast::<libc::inotify_event>(),
+                    )
+                };
+                let name_start = offset + EVENT_SIZE;
+                let name_end = name_start + event.len as usize;
+                offset = name_end;
+
+                let Some(dir) = self.dirs.get(&event.wd) else {
+                    continue;
+                };
+                let name: Vec<u8> = buffer[name_start..name_end]
+                    .iter()
+                    .copied()
+                    .take_while(|&b| b != 0)
+                    .collect();
+                if name.is_empty() {
+                    continue;
+                }
+                let path = dir.join(OsStr::from_bytes(&name));
+                if event.mask & libc::IN_ISDIR != 0 {
+                    if event.mask & libc::IN_CREATE != 0 {
+                        new_dirs.push(path);
+                    }
+                } else {
+                    changed.push(path);
+                }
+            }
+
+            // directories created after the start are watched as well.
+            for dir in new_dirs {
+                self.add(&dir)?;
+            }
+
+            Ok(changed)
+        }
+    }
+
+    impl Drop for Inotify {
+        fn drop(&mut self) {
+            // SAFETY: `fd` is owned by this struct and closed only once.
+            unsafe { libc::close(self.fd) };
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod polling {
+    use std::{
+        collections::HashMap,
+        fs, io,
+        path::{Path, PathBuf},
+        thread,
+        time::{Duration, Instant, SystemTime},
+    };
+
+    /// The interval in which modification times are compared.
+    const INTERVAL: Duration = Duration::from_millis(250);
+
+    #[derive(Default)]
+    pub struct Polling {
+        dirs: Vec<PathBuf>,
+        files: HashMap<PathBuf, SystemTime>,
+    }
+
+    impl Polling {
+        pub fn add(&mut self, dir: &Path) -> io::Result<()> {
+            self.dirs.push(dir.to_path_buf());
+            self.files.extend(Self::scan(dir));
+            Ok(())
+        }
+
+        fn scan(dir: &Path) -> Vec<(PathBuf, SystemTime)> {
+            let Ok(entries) = fs::read_dir(dir) else {
+                return vec![];
+            };
+            entries
+                .filter_map(Result::ok)
+                .filter_map(|entry| {
+                    let metadata = entry.metadata().ok()?;
+                    metadata
+                        .is_file()
+                        .then(|| Some((entry.path(), metadata.modified().ok()?)))?
+                })
+                .collect()
+        }
+
+        /// Waits up to `timeout` (forever if [`None`]) for changes and returns the changed paths.
+        pub fn wait(&mut self, timeout: Option<Duration>) -> io::Result<Vec<PathBuf>> {
+            let start = Instant::now();
+            loop {
+                let files: HashMap<PathBuf, SystemTime> =
+                    self.dirs.iter().flat_map(|dir| Self::scan(dir)).collect();
+                let mut changed: Vec<PathBuf> = files
+                    .iter()
+                    .filter(|(path, time)| self.files.get(*path) != Some(*time))
+                    .map(|(path, _)| path.clone())
+                    .collect();
+                changed.extend(
+                    self.files
+                        .keys()
+                        .filter(|path| !files.contains_key(*path))
+                        .cloned(),
+                );
+                self.files = files;
+
+                if !changed.is_empty() || timeout.is_some_and(|t| start.elapsed() >= t) {
+                    return Ok(changed);
+                }
+                thread::sleep(INTERVAL);
+            }
+        }
+    }
+}